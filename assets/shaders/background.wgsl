@@ -3,6 +3,8 @@
 
 struct CustomMaterial {
     color: vec4<f32>,
+    // 1 animates, 0 holds still for reduced motion
+    motion: f32,
 };
 
 @group(1) @binding(0)
@@ -16,6 +18,8 @@ var base_color_sampler: sampler;
 const PI: f32 = 3.14159265358979323846;
 const purple: vec3<f32> = vec3<f32>(0.298, 0.176, 0.459);
 const yellow: vec3<f32> = vec3<f32>(0.675, 0.635, 0.22);
+// the moment shown when the animation is held still
+const STILL_TIME: f32 = 20.0;
 const ITS: f32 = 100.0;
 
 fn txnoise(x: vec3<f32>) -> f32 {
@@ -93,7 +97,8 @@ fn fragment(in: MeshVertexOutput) -> @location(0) vec4<f32> {
     var c: vec3<f32> = vec3<f32>(1.0);
     var a: f32 = 0.0;
 
-    let n = globals.time * 2.5;
+    let time = mix(STILL_TIME, globals.time, material.motion);
+    let n = time * 2.5;
 
     // a += txnoise(p * 1000.0 + vec3(0.0, n * 1.0, 0.0));
     // a += step(0.35, txnoise(p * 4000.0 + vec3(0.0, n * 2.0, 0.0))) * 0.2;
@@ -110,17 +115,17 @@ fn fragment(in: MeshVertexOutput) -> @location(0) vec4<f32> {
     a += ambient * 0.1;
 
     var gp = p;
-    let gw = min(1.0, max(0.0, (globals.time * 0.2 - 2.0)));
+    let gw = min(1.0, max(0.0, (time * 0.2 - 2.0)));
     // gp.y += sin(gp.y * 15.0) * 0.5;
     var galaxy_gradient = 0.0;
-    galaxy_gradient += sin(gp.y * 35.0 + gp.x * 5.0 + 4.0 + globals.time * 0.3 + sin(gp.y * 10.0)) - 0.3;
+    galaxy_gradient += sin(gp.y * 35.0 + gp.x * 5.0 + 4.0 + time * 0.3 + sin(gp.y * 10.0)) - 0.3;
     galaxy_gradient -= sin(gp.x * 20.0 + gp.y * 100.0) - 1.0;
     // galaxy_gradient *= 1.0 + sin(gp.x * 40.0);
     galaxy_gradient = clamp(galaxy_gradient - 2.0, 0.0, 1.0);
 
     a += galaxy_gradient * 0.05 * gw;
-    c.r -= galaxy_gradient * (1.0 + sin(globals.time * 0.1) * 0.8) * gw;
-    c.b -= galaxy_gradient * (0.5 + sin(globals.time * 0.1 + 2.0) * 0.8) * gw;
+    c.r -= galaxy_gradient * (1.0 + sin(time * 0.1) * 0.8) * gw;
+    c.b -= galaxy_gradient * (0.5 + sin(time * 0.1 + 2.0) * 0.8) * gw;
     
 
     // c = step(vec3(0.1), c);
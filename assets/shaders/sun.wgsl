@@ -3,6 +3,8 @@
 
 struct CustomMaterial {
     color: vec4<f32>,
+    // 1 animates, 0 holds still for reduced motion
+    motion: f32,
 };
 
 @group(1) @binding(0)
@@ -16,6 +18,8 @@ var base_color_sampler: sampler;
 const PI: f32 = 3.14159265358979323846;
 const purple: vec3<f32> = vec3<f32>(0.298, 0.176, 0.459);
 const yellow: vec3<f32> = vec3<f32>(0.675, 0.635, 0.22);
+// the moment shown when the animation is held still
+const STILL_TIME: f32 = 20.0;
 const ITS: f32 = 10.0;
 
 fn txnoise(x: vec3<f32>) -> f32 {
@@ -90,10 +94,11 @@ fn fragment(in: MeshVertexOutput) -> @location(0) vec4<f32> {
 
     var c: vec3<f32> = vec3<f32>(0.0);
     
+    let time = mix(STILL_TIME, globals.time, material.motion);
     let hr: f32 = 0.05;
     var z: f32 = -hr;
     while(z < hr) {
-        c = c + scene(vec3<f32>(uv, z), globals.time + 10.0);
+        c = c + scene(vec3<f32>(uv, z), time + 10.0);
         z = z + 1.0 / ITS;
     }
     c = clamp(c, vec3(0.0), vec3(3.0));
@@ -0,0 +1,359 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    button::{menu_item_style, overlay_panel, set_button_text, ButtonCommands},
+    gameplay::{CelestialBody, Debris, GameState, OnHit, PickedUp, PICKUP_RADIUS},
+    locale::{Locale, Localized},
+    pause::PauseState,
+    PrimaryColorHue, PRIMARY_COLOR_HUE,
+};
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OpenAccessibility>()
+            .insert_resource(Accessibility::default())
+            .add_systems(OnExit(GameState::Menu), close_accessibility)
+            .add_systems(OnExit(PauseState::Paused), close_accessibility)
+            .add_systems(
+                Update,
+                (
+                    open_accessibility,
+                    interact_accessibility_buttons,
+                    update_accessibility_buttons.run_if(
+                        resource_changed::<Accessibility>().or_else(resource_changed::<Locale>()),
+                    ),
+                    apply_palette.run_if(resource_changed::<Accessibility>()),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, outline_hazards.run_if(high_contrast));
+    }
+}
+
+// show the accessibility options, on top of settings
+#[derive(Event)]
+pub struct OpenAccessibility;
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Accessibility {
+    // multiplies every screen shake, 0 turns it off
+    pub shake: f32,
+    // no shake, no big flashes and a background that holds still
+    pub reduced_motion: bool,
+    // outlines around debris and anything that wrecks a crate
+    pub high_contrast: bool,
+    pub palette: Palette,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility {
+            shake: 1.0,
+            reduced_motion: false,
+            high_contrast: false,
+            palette: Palette::Standard,
+        }
+    }
+}
+
+// what the shake button steps through
+const SHAKE_STEPS: [f32; 4] = [1.0, 0.5, 0.25, 0.0];
+
+impl Accessibility {
+    // how much of a shake actually reaches the camera
+    pub fn shake_scale(&self) -> f32 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            self.shake
+        }
+    }
+
+    // how fast the background shaders animate
+    pub fn motion(&self) -> f32 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    fn next_shake(&self) -> f32 {
+        let current = SHAKE_STEPS
+            .iter()
+            .position(|step| (step - self.shake).abs() < 0.01);
+        current.map_or(SHAKE_STEPS[0], |i| SHAKE_STEPS[(i + 1) % SHAKE_STEPS.len()])
+    }
+}
+
+// colours picked so the things that matter can still be told apart
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Palette {
+    #[default]
+    Standard,
+    // deuteranopia and protanopia
+    RedGreen,
+    // tritanopia
+    BlueYellow,
+}
+
+impl Palette {
+    const ALL: [Palette; 3] = [Palette::Standard, Palette::RedGreen, Palette::BlueYellow];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette.standard",
+            Palette::RedGreen => "palette.red_green",
+            Palette::BlueYellow => "palette.blue_yellow",
+        }
+    }
+
+    fn next(&self) -> Palette {
+        let i = Palette::ALL.iter().position(|p| p == self).unwrap_or(0);
+        Palette::ALL[(i + 1) % Palette::ALL.len()]
+    }
+
+    // a ui hue moved onto the two hues this palette keeps apart
+    pub fn hue(&self, hue: f32) -> f32 {
+        match self {
+            Palette::Standard => hue,
+            // blue or orange
+            Palette::RedGreen if (0.25..0.75).contains(&hue) => 0.6,
+            Palette::RedGreen => 0.09,
+            // teal or pink
+            Palette::BlueYellow if (0.35..0.7).contains(&hue) => 0.5,
+            Palette::BlueYellow => 0.95,
+        }
+    }
+
+    pub fn debris_color(&self) -> Color {
+        match self {
+            Palette::Standard => Color::LIME_GREEN,
+            Palette::RedGreen => Color::rgb(0.34, 0.71, 0.91),
+            Palette::BlueYellow => Color::rgb(0.0, 0.62, 0.45),
+        }
+    }
+
+    pub fn hazard_color(&self) -> Color {
+        match self {
+            Palette::Standard => Color::RED,
+            Palette::RedGreen => Color::rgb(0.9, 0.62, 0.0),
+            Palette::BlueYellow => Color::rgb(0.8, 0.47, 0.65),
+        }
+    }
+}
+
+pub fn high_contrast(accessibility: Res<Accessibility>) -> bool {
+    accessibility.high_contrast
+}
+
+#[derive(Component)]
+struct AccessibilityPanel;
+
+#[derive(Component)]
+enum AccessibilityButton {
+    Shake,
+    ReducedMotion,
+    HighContrast,
+    Palette,
+    Back,
+}
+
+impl AccessibilityButton {
+    fn text(&self, accessibility: &Accessibility, locale: &Locale) -> String {
+        match self {
+            AccessibilityButton::Shake if accessibility.shake == 0.0 => {
+                locale.text("accessibility.shake_off")
+            }
+            AccessibilityButton::Shake => {
+                let percent = (accessibility.shake * 100.0).round();
+                locale.format("accessibility.shake", &[("percent", &percent)])
+            }
+            AccessibilityButton::ReducedMotion => locale.format(
+                "accessibility.reduced_motion",
+                &[("state", &locale.on_off(accessibility.reduced_motion))],
+            ),
+            AccessibilityButton::HighContrast => locale.format(
+                "accessibility.high_contrast",
+                &[("state", &locale.on_off(accessibility.high_contrast))],
+            ),
+            AccessibilityButton::Palette => locale.format(
+                "accessibility.palette",
+                &[("palette", &locale.text(accessibility.palette.key()))],
+            ),
+            AccessibilityButton::Back => locale.text("common.back"),
+        }
+    }
+}
+
+fn open_accessibility(
+    mut commands: Commands,
+    mut ev_open: EventReader<OpenAccessibility>,
+    q_panel: Query<(), With<AccessibilityPanel>>,
+    accessibility: Res<Accessibility>,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    if ev_open.iter().count() == 0 || !q_panel.is_empty() {
+        return;
+    }
+
+    let buttons = [
+        AccessibilityButton::Shake,
+        AccessibilityButton::ReducedMotion,
+        AccessibilityButton::HighContrast,
+        AccessibilityButton::Palette,
+        AccessibilityButton::Back,
+    ]
+    .into_iter()
+    .map(|button| {
+        commands
+            .spawn_text_button(&button.text(&accessibility, &locale), primary_color_hue.0)
+            .insert((button, menu_item_style()))
+            .id()
+    })
+    .collect::<Vec<_>>();
+
+    commands
+        .spawn((AccessibilityPanel, overlay_panel(30)))
+        .with_children(|parent| {
+            parent.spawn((
+                Localized::new("accessibility.title"),
+                TextBundle::from_section(
+                    locale.text("accessibility.title"),
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            ));
+        })
+        .push_children(&buttons);
+}
+
+fn close_accessibility(mut commands: Commands, q_panel: Query<Entity, With<AccessibilityPanel>>) {
+    for ent in q_panel.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_accessibility_buttons(
+    mut commands: Commands,
+    q_button: Query<(&Interaction, &AccessibilityButton), Changed<Interaction>>,
+    q_panel: Query<Entity, With<AccessibilityPanel>>,
+    mut accessibility: ResMut<Accessibility>,
+) {
+    for (interaction, button) in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            AccessibilityButton::Shake => accessibility.shake = accessibility.next_shake(),
+            AccessibilityButton::ReducedMotion => {
+                accessibility.reduced_motion = !accessibility.reduced_motion
+            }
+            AccessibilityButton::HighContrast => {
+                accessibility.high_contrast = !accessibility.high_contrast
+            }
+            AccessibilityButton::Palette => accessibility.palette = accessibility.palette.next(),
+            AccessibilityButton::Back => {
+                for ent in q_panel.iter() {
+                    commands.entity(ent).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+fn update_accessibility_buttons(
+    q_button: Query<(&AccessibilityButton, &Children)>,
+    mut q_text: Query<&mut Text>,
+    accessibility: Res<Accessibility>,
+    locale: Res<Locale>,
+) {
+    for (button, children) in q_button.iter() {
+        set_button_text(children, &mut q_text, &button.text(&accessibility, &locale));
+    }
+}
+
+// the backdrop follows the palette, and goes almost black for high contrast
+fn apply_palette(accessibility: Res<Accessibility>, mut clear_color: ResMut<ClearColor>) {
+    let hue = accessibility.palette.hue(PRIMARY_COLOR_HUE);
+    let lightness = if accessibility.high_contrast {
+        0.03
+    } else {
+        0.1
+    };
+    clear_color.0 = Color::hsl(hue * 360.0, 0.2, lightness);
+}
+
+// a few rings each, so they read as a thick line
+const OUTLINE_RINGS: [f32; 3] = [0.0, 0.15, 0.3];
+
+fn outline_hazards(
+    mut gizmos: Gizmos,
+    accessibility: Res<Accessibility>,
+    q_debris: Query<&GlobalTransform, (With<Debris>, Without<PickedUp>)>,
+    q_bodies: Query<(&GlobalTransform, &CelestialBody)>,
+) {
+    let palette = accessibility.palette;
+    let outlines = q_debris
+        .iter()
+        .map(|transform| (transform, PICKUP_RADIUS, palette.debris_color()))
+        .chain(
+            q_bodies
+                .iter()
+                // the sun is where crates are meant to go
+                .filter(|(_, body)| body.on_hit != OnHit::Incinerate)
+                .map(|(transform, body)| (transform, body.radius, palette.hazard_color())),
+        );
+
+    for (transform, radius, color) in outlines {
+        for ring in OUTLINE_RINGS {
+            gizmos.circle(transform.translation(), Vec3::Z, radius + ring, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_motion_stops_all_shake() {
+        let mut accessibility = Accessibility::default();
+        assert_eq!(accessibility.shake_scale(), 1.0);
+
+        accessibility.shake = accessibility.next_shake();
+        assert_eq!(accessibility.shake_scale(), 0.5);
+
+        accessibility.reduced_motion = true;
+        assert_eq!(accessibility.shake_scale(), 0.0);
+        assert_eq!(accessibility.motion(), 0.0);
+
+        // stepping past off starts over at full
+        accessibility.shake = 0.0;
+        assert_eq!(accessibility.next_shake(), 1.0);
+    }
+
+    #[test]
+    fn palettes_keep_menu_hues_apart() {
+        // the regular menu and endless mode
+        let hues = [PRIMARY_COLOR_HUE, 0.8];
+        for palette in Palette::ALL {
+            let [a, b] = hues.map(|hue| palette.hue(hue));
+            assert!((a - b).abs() > 0.2, "{:?}", palette);
+            assert_ne!(palette.debris_color(), palette.hazard_color());
+        }
+    }
+}
@@ -1,12 +1,12 @@
-use bevy::{prelude::*, ecs::system::EntityCommands};
+use bevy::{ecs::system::EntityCommands, prelude::*, ui::FocusPolicy};
 
 use crate::PrimaryColorHue;
 
-fn button_background_color(hue: f32) -> Color {
+pub fn button_background_color(hue: f32) -> Color {
     Color::hsl(hue * 360.0, 0.5, 0.4)
 }
 
-fn button_hover_color(hue: f32) -> Color {
+pub fn button_hover_color(hue: f32) -> Color {
     Color::hsl(hue * 360.0, 0.5, 0.45)
 }
 
@@ -27,6 +27,72 @@ pub fn interact_button(
     }
 }
 
+// big centered button, override fields to place it elsewhere
+pub fn button_style() -> Style {
+    Style {
+        width: Val::Px(240.0),
+        height: Val::Px(100.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        margin: UiRect::new(Val::Auto, Val::Auto, Val::Auto, Val::Vh(30.0)),
+        border: UiRect {
+            left: Val::Px(1.0),
+            right: Val::Px(1.0),
+            top: Val::Px(1.0),
+            bottom: Val::Px(4.0),
+        },
+        ..default()
+    }
+}
+
+// for a column of buttons in an overlay panel
+pub fn menu_item_style() -> Style {
+    Style {
+        width: Val::Px(360.0),
+        height: Val::Px(80.0),
+        margin: UiRect::all(Val::Px(8.0)),
+        ..button_style()
+    }
+}
+
+// change the label of a button from spawn_text_button, shadow included
+pub fn set_button_text(children: &Children, q_text: &mut Query<&mut Text>, value: &str) {
+    for child in children.iter() {
+        if let Ok(mut text) = q_text.get_mut(*child) {
+            text.sections[0].value = value.to_string();
+        }
+    }
+}
+
+// on every overlay_panel, so keys can leave the screen underneath alone
+#[derive(Component)]
+pub struct Overlay;
+
+pub fn overlay_open(q_overlay: Query<(), With<Overlay>>) -> bool {
+    !q_overlay.is_empty()
+}
+
+// dark full screen backdrop for menus drawn over the game, later ones need a higher z
+pub fn overlay_panel(z: i32) -> (NodeBundle, Overlay) {
+    let node = NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Color::BLACK.with_a(0.85).into(),
+        // keep clicks from reaching buttons underneath
+        focus_policy: FocusPolicy::Block,
+        z_index: ZIndex::Global(z),
+        ..default()
+    };
+    (node, Overlay)
+}
+
 pub trait ButtonCommands<'w, 's> {
     fn spawn_text_button<'a>(&'a mut self, text: &str, hue: f32) -> EntityCommands<'w, 's, 'a>;
 }
@@ -36,20 +102,7 @@ impl<'w, 's> ButtonCommands<'w, 's> for Commands<'w, 's> {
         let mut e = self.spawn_empty();
 
         e.insert(ButtonBundle {
-            style: Style {
-                width: Val::Px(240.0),
-                height: Val::Px(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                margin: UiRect::new(Val::Auto, Val::Auto, Val::Auto, Val::Vh(30.0)),
-                border: UiRect {
-                    left: Val::Px(1.0),
-                    right: Val::Px(1.0),
-                    top: Val::Px(1.0),
-                    bottom: Val::Px(4.0),
-                },
-                ..default()
-            },
+            style: button_style(),
             border_color: Color::BLACK.with_a(0.5).into(),
             background_color: button_background_color(hue).into(),
             ..default()
@@ -89,4 +142,4 @@ impl<'w, 's> ButtonCommands<'w, 's> for Commands<'w, 's> {
         });
         e
     }
-}
\ No newline at end of file
+}
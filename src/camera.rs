@@ -0,0 +1,295 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    math::{vec3, Vec3Swizzles},
+    prelude::*,
+};
+
+use crate::{
+    accessibility::Accessibility,
+    button::overlay_open,
+    gameplay::{CelestialBody, Crate, CurrentCrate, Debris, PickedUp, Velocity, VELOCITY_SCALE},
+};
+
+pub struct CameraDirectorPlugin;
+
+impl Plugin for CameraDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CameraDirector::default())
+            .insert_resource(CameraShake(1.0))
+            .add_systems(
+                Update,
+                (
+                    zoom_manually.run_if(not(overlay_open)),
+                    direct_camera,
+                    settle_shake,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// world units from the bottom to the top of the screen at a scale of 1
+pub const VIEW_HEIGHT: f32 = 720.0;
+
+// how the camera frames things, tweakable without touching the systems
+#[derive(Resource)]
+pub struct CameraDirector {
+    // half the size of the box around the middle of the screen a followed crate can move in
+    // before the camera goes after it
+    pub dead_zone: Vec2,
+    // seconds of crate velocity the camera looks ahead by
+    pub look_ahead: f32,
+    // space kept around everything framed
+    pub margin: f32,
+    pub follow_scale: f32,
+    // how far outside the level a crate can drift before zooming out to keep both in view
+    pub drift_distance: f32,
+    // fraction of the framed scale the player can zoom to
+    pub manual_limits: (f32, f32),
+    // the scale never goes past these, whatever is framed
+    pub scale_limits: (f32, f32),
+    // how quickly the camera catches up, per second
+    pub smoothing: f32,
+}
+
+impl Default for CameraDirector {
+    fn default() -> Self {
+        CameraDirector {
+            dead_zone: Vec2::new(6.0, 4.0),
+            look_ahead: 0.4,
+            margin: 6.0,
+            follow_scale: 0.09,
+            drift_distance: 20.0,
+            manual_limits: (0.5, 2.5),
+            scale_limits: (0.05, 0.4),
+            smoothing: 2.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct CameraShake(pub f32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    // the whole level, while aiming
+    Overview,
+    // close up on a crate in flight
+    Follow,
+    // a crate far from the level, wide enough to see both
+    Drift,
+}
+
+// where the camera is headed, kept apart from the transform so shake doesn't feed back into it
+#[derive(Component)]
+pub struct DirectedCamera {
+    pub framing: Framing,
+    pub center: Vec2,
+    pub scale: f32,
+    // pinched or scrolled on top of whatever is framed
+    pub manual_zoom: f32,
+}
+
+impl Default for DirectedCamera {
+    fn default() -> Self {
+        DirectedCamera {
+            framing: Framing::Overview,
+            center: Vec2::ZERO,
+            scale: 0.12,
+            manual_zoom: 1.0,
+        }
+    }
+}
+
+// scale that fits all of `size` on a screen with this aspect ratio
+fn scale_to_fit(size: Vec2, aspect: f32) -> f32 {
+    size.y.max(size.x / aspect) / VIEW_HEIGHT
+}
+
+// moves `center` only as far as needed to keep `target` inside the dead zone around it
+fn apply_dead_zone(center: Vec2, target: Vec2, dead_zone: Vec2) -> Vec2 {
+    target - (target - center).clamp(-dead_zone, dead_zone)
+}
+
+// the framing, where to point and how far to zoom, for a level and maybe a crate in flight
+fn frame(
+    director: &CameraDirector,
+    level: Rect,
+    flight: Option<(Vec2, Vec2)>,
+    aspect: f32,
+) -> (Framing, Vec2, f32) {
+    let Some((position, velocity)) = flight else {
+        let shot = level.inset(director.margin);
+        return (
+            Framing::Overview,
+            shot.center(),
+            scale_to_fit(shot.size(), aspect),
+        );
+    };
+
+    if level.inset(director.drift_distance).contains(position) {
+        let lead = position + velocity * VELOCITY_SCALE * director.look_ahead;
+        return (Framing::Follow, lead, director.follow_scale);
+    }
+
+    let shot = level.union_point(position).inset(director.margin);
+    let scale = scale_to_fit(shot.size(), aspect).max(director.follow_scale);
+    (Framing::Drift, shot.center(), scale)
+}
+
+// scroll up or spread two fingers to zoom in
+fn zoom_manually(
+    mut ev_wheel: EventReader<MouseWheel>,
+    touches: Res<Touches>,
+    director: Res<CameraDirector>,
+    mut q_camera: Query<&mut DirectedCamera>,
+) {
+    let mut zoom = ev_wheel
+        .iter()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => 0.9f32.powf(wheel.y),
+            MouseScrollUnit::Pixel => 0.9f32.powf(wheel.y / 24.0),
+        })
+        .product::<f32>();
+
+    let fingers = touches.iter().collect::<Vec<_>>();
+    if let [a, b] = fingers[..] {
+        let before = a.previous_position().distance(b.previous_position());
+        let now = a.position().distance(b.position());
+        if before > 0.0 && now > 0.0 {
+            zoom *= before / now;
+        }
+    }
+    if zoom == 1.0 {
+        return;
+    }
+
+    let (min, max) = director.manual_limits;
+    for mut camera in q_camera.iter_mut() {
+        camera.manual_zoom = (camera.manual_zoom * zoom).clamp(min, max);
+    }
+}
+
+fn direct_camera(
+    time: Res<Time>,
+    director: Res<CameraDirector>,
+    shake: Res<CameraShake>,
+    accessibility: Res<Accessibility>,
+    q_crate: Query<(&Transform, &Velocity), With<CurrentCrate>>,
+    q_bodies: Query<(&Transform, &CelestialBody)>,
+    q_debris: Query<&Transform, (With<Debris>, Without<PickedUp>)>,
+    mut q_camera: Query<
+        (
+            &Camera,
+            &mut Transform,
+            &mut Projection,
+            &mut DirectedCamera,
+        ),
+        (Without<CelestialBody>, Without<Debris>, Without<Crate>),
+    >,
+) {
+    // everything that makes up the level, sun and earth included
+    let mut level = Rect::from_center_size(Vec2::ZERO, Vec2::ZERO);
+    for (transform, body) in q_bodies.iter() {
+        let body_rect =
+            Rect::from_center_half_size(transform.translation.xy(), Vec2::splat(body.radius));
+        level = level.union(body_rect);
+    }
+    for transform in q_debris.iter() {
+        level = level.union_point(transform.translation.xy());
+    }
+
+    let flight = q_crate
+        .get_single()
+        .ok()
+        .map(|(transform, velocity)| (transform.translation.xy(), velocity.0));
+
+    // add shake (based on quantized time)
+    let shake_amount = shake.0 * accessibility.shake_scale();
+    let shake_n = (time.elapsed_seconds() * 100.0).floor();
+    let shake_x = (shake_n * 55.5).sin() * shake_amount;
+    let shake_y = (shake_n * 77.5).cos() * shake_amount;
+    let shake_vec3 = vec3(shake_x, shake_y, 0.0);
+
+    let t = (time.delta_seconds() * director.smoothing).min(1.0);
+
+    for (camera, mut transform, mut projection, mut directed) in q_camera.iter_mut() {
+        let aspect = camera
+            .logical_viewport_size()
+            .map_or(16.0 / 9.0, |size| size.x / size.y);
+        let (framing, target, scale) = frame(&director, level, flight, aspect);
+
+        let target = match framing {
+            Framing::Follow => apply_dead_zone(directed.center, target, director.dead_zone),
+            _ => target,
+        };
+        directed.framing = framing;
+        directed.center = directed.center.lerp(target, t);
+        directed.scale += (scale - directed.scale) * t;
+
+        transform.translation = directed.center.extend(10.0) + shake_vec3;
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            let (min, max) = director.scale_limits;
+            orthographic.scale = (directed.scale * directed.manual_zoom).clamp(min, max);
+        }
+    }
+}
+
+fn settle_shake(time: Res<Time>, mut camera_shake: ResMut<CameraShake>) {
+    camera_shake.0 *= 1.0 - time.delta_seconds() * 8.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+
+    #[test]
+    fn dead_zone_only_moves_camera_as_far_as_needed() {
+        let dead_zone = vec2(6.0, 4.0);
+        assert_eq!(
+            apply_dead_zone(Vec2::ZERO, vec2(5.0, -3.0), dead_zone),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            apply_dead_zone(Vec2::ZERO, vec2(10.0, -3.0), dead_zone),
+            vec2(4.0, 0.0)
+        );
+        assert_eq!(
+            apply_dead_zone(vec2(1.0, 1.0), vec2(1.0, 10.0), dead_zone),
+            vec2(1.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn framing_follows_the_crate_and_widens_when_it_drifts() {
+        let director = CameraDirector::default();
+        let level = Rect::new(-10.0, -30.0, 10.0, 15.0);
+
+        // the whole level, margins and all, fits on screen while aiming
+        let (framing, center, scale) = frame(&director, level, None, 1.0);
+        assert_eq!(framing, Framing::Overview);
+        assert_eq!(center, level.center());
+        assert_eq!(scale * VIEW_HEIGHT, 45.0 + 2.0 * director.margin);
+
+        // ahead of a crate in flight, closer in
+        let (framing, center, scale) = frame(
+            &director,
+            level,
+            Some((vec2(0.0, 10.0), vec2(1.0, 0.0))),
+            1.0,
+        );
+        assert_eq!(framing, Framing::Follow);
+        assert!(center.x > 0.0);
+        assert_eq!(scale, director.follow_scale);
+
+        // far away, both the crate and the level are in view
+        let far = vec2(200.0, 0.0);
+        let (framing, center, scale) = frame(&director, level, Some((far, Vec2::X)), 1.0);
+        assert_eq!(framing, Framing::Drift);
+        let visible = Rect::from_center_size(center, Vec2::splat(scale * VIEW_HEIGHT));
+        assert!(visible.contains(far));
+        assert!(visible.contains(level.min) && visible.contains(level.max));
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
+
+use crate::{gameplay::Cargo, utils::AssetHandle};
+
+pub struct CargoPlugin;
+
+impl Plugin for CargoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<CargoCatalog>()
+            .init_asset_loader::<CargoCatalogLoader>()
+            .add_systems(Startup, load_catalog)
+            .add_systems(Update, apply_catalog);
+    }
+}
+
+// everything that can end up in a crate, loaded from assets/*.catalog.ron
+#[derive(Deserialize, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "5d2f8a91-0c4e-4b7a-a3e6-7f19b2c84d05"]
+pub struct CargoCatalog {
+    pub cargo: Vec<CargoData>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CargoData {
+    pub name: String,
+    // picked this many times as often as cargo with the default of 1
+    #[serde(default = "one")]
+    pub weight: f32,
+    // multiplies how heavy the crate starts out
+    #[serde(default = "one")]
+    pub mass: f32,
+    // looped while the crate is in the air, louder and faster the closer it gets to the sun
+    #[serde(default)]
+    pub sound: Option<String>,
+    // left out of crates before this level
+    #[serde(default)]
+    pub unlock_level: Option<usize>,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+// plain crates until the catalog has loaded, or if it has nothing for this level
+impl Default for CargoCatalog {
+    fn default() -> Self {
+        CargoCatalog {
+            cargo: vec![CargoData {
+                name: "Crate".to_string(),
+                weight: 1.0,
+                mass: 1.0,
+                sound: None,
+                unlock_level: None,
+            }],
+        }
+    }
+}
+
+impl CargoCatalog {
+    // something unlocked by `level`, more likely the heavier its weight
+    pub fn pick(&self, level: usize, rng: &mut impl Rng) -> CargoData {
+        let unlocked = self
+            .cargo
+            .iter()
+            .filter(|cargo| cargo.unlock_level.map_or(true, |unlock| unlock <= level))
+            .collect::<Vec<_>>();
+
+        match unlocked.choose_weighted(rng, |cargo| cargo.weight) {
+            Ok(cargo) => (*cargo).clone(),
+            Err(_) => CargoCatalog::default().cargo.remove(0),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CargoCatalogLoader;
+
+impl AssetLoader for CargoCatalogLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let catalog = ron::de::from_bytes::<CargoCatalog>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(catalog));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalog.ron"]
+    }
+}
+
+fn load_catalog(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AssetHandle::<Cargo, CargoCatalog>::new(
+        asset_server.load("cargo.catalog.ron"),
+    ));
+}
+
+// picked up again whenever the file changes, so writers can try out new cargo without a restart
+fn apply_catalog(
+    mut ev_asset: EventReader<AssetEvent<CargoCatalog>>,
+    catalog: Res<AssetHandle<Cargo, CargoCatalog>>,
+    catalogs: Res<Assets<CargoCatalog>>,
+    mut cargo: ResMut<Cargo>,
+) {
+    for event in ev_asset.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            if *handle != catalog.handle {
+                continue;
+            }
+            if let Some(loaded) = catalogs.get(handle) {
+                cargo.0 = loaded.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn catalog_fields_are_optional() {
+        let catalog = ron::from_str::<CargoCatalog>(
+            r#"(cargo: [
+                (name: "Car tires"),
+                (name: "Poor fella", weight: 2.0, sound: Some("aaa.ogg"), unlock_level: Some(3)),
+            ])"#,
+        )
+        .unwrap();
+
+        assert_eq!(catalog.cargo[0].weight, 1.0);
+        assert_eq!(catalog.cargo[0].mass, 1.0);
+        assert_eq!(catalog.cargo[0].sound, None);
+        assert_eq!(catalog.cargo[1].sound.as_deref(), Some("aaa.ogg"));
+        assert_eq!(catalog.cargo[1].unlock_level, Some(3));
+    }
+
+    #[test]
+    fn locked_cargo_is_never_picked() {
+        let cargo = |name: &str, unlock_level| CargoData {
+            name: name.to_string(),
+            unlock_level: Some(unlock_level),
+            ..CargoCatalog::default().cargo[0].clone()
+        };
+        let catalog = CargoCatalog {
+            cargo: vec![cargo("Car tires", 2), cargo("Nuclear waste", 4)],
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50 {
+            assert_eq!(catalog.pick(3, &mut rng).name, "Car tires");
+        }
+        // nothing unlocked yet still fills the crate with something
+        assert_eq!(catalog.pick(1, &mut rng).name, "Crate");
+    }
+}
@@ -0,0 +1,779 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    math::Vec3Swizzles,
+    prelude::*,
+    ui::UiSystem,
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{Aim, CrateLaunched, Earth, GameState, LaunchPower},
+    locale::Locale,
+    pause::pointer_over_ui,
+    rebind::Listening,
+    replay::is_replaying,
+};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PlayerInput::default())
+            .insert_resource(Bindings::default())
+            .add_systems(PreUpdate, read_player_input.after(UiSystem::Focus))
+            .add_systems(
+                Update,
+                (
+                    rumble_while_charging.run_if(in_state(GameState::ChargingLaunch)),
+                    rumble_on_launch,
+                )
+                    .run_if(not(is_replaying)),
+            )
+            .add_systems(OnExit(GameState::ChargingLaunch), stop_rumble);
+    }
+}
+
+// radians per second the arrow keys turn the cannon
+const KEYBOARD_AIM_SPEED: f32 = 2.0;
+// sticks and triggers rest a little off zero
+const STICK_DEADZONE: f32 = 0.3;
+const TRIGGER_THRESHOLD: f32 = 0.3;
+// menus move one entry per push, the stick has to come back first
+const STICK_STEP_THRESHOLD: f32 = 0.5;
+
+// everything the player can do, whatever it's bound to
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    Aim,
+    Charge,
+    Pause,
+    Restart,
+    // back to the main menu
+    Menu,
+    // stop watching a replay
+    Skip,
+    // play from the main menu, or pick whatever's focused
+    Confirm,
+    Back,
+    LevelSelect,
+    // moving between menu entries
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+// where an action is listened for, one input can mean something different in each
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Context {
+    Playing,
+    Menus,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::Aim,
+        Action::Charge,
+        Action::Pause,
+        Action::Restart,
+        Action::Menu,
+        Action::Skip,
+        Action::Confirm,
+        Action::Back,
+        Action::LevelSelect,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::Aim => "action.aim",
+            Action::Charge => "action.charge",
+            Action::Pause => "action.pause",
+            Action::Restart => "action.restart",
+            Action::Menu => "action.menu",
+            Action::Skip => "action.skip",
+            Action::Confirm => "action.confirm",
+            Action::Back => "action.back",
+            Action::LevelSelect => "action.level_select",
+            Action::Up => "action.up",
+            Action::Down => "action.down",
+            Action::Left => "action.left",
+            Action::Right => "action.right",
+        }
+    }
+
+    pub fn context(&self) -> Context {
+        match self {
+            Action::Aim
+            | Action::Charge
+            | Action::Pause
+            | Action::Restart
+            | Action::Menu
+            | Action::Skip => Context::Playing,
+            Action::Confirm
+            | Action::Back
+            | Action::LevelSelect
+            | Action::Up
+            | Action::Down
+            | Action::Left
+            | Action::Right => Context::Menus,
+        }
+    }
+
+    pub fn name(&self, locale: &Locale) -> String {
+        locale.text(self.key())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Touch,
+    Gamepad(GamepadButtonType),
+    // the rest only aim: at the cursor or finger, where a stick points,
+    // or turning with one key or button per direction. a stick also moves through menus
+    Pointer,
+    Stick(Stick),
+    Keys(KeyCode, KeyCode),
+    Buttons(GamepadButtonType, GamepadButtonType),
+}
+
+// which column of the controls screen a binding is shown in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingKind {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+impl Binding {
+    pub fn kind(&self) -> BindingKind {
+        match self {
+            Binding::Key(_) | Binding::Keys(..) => BindingKind::Keyboard,
+            Binding::Mouse(_) | Binding::Touch | Binding::Pointer => BindingKind::Mouse,
+            Binding::Gamepad(_) | Binding::Stick(_) | Binding::Buttons(..) => BindingKind::Gamepad,
+        }
+    }
+
+    // sticks, the pointer and touch stay put when the buttons next to them are rebound
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, Binding::Pointer | Binding::Stick(_) | Binding::Touch)
+    }
+
+    // the single keys and buttons it listens to, two bindings sharing one conflict
+    fn inputs(&self) -> Vec<Binding> {
+        match *self {
+            Binding::Keys(left, right) => vec![Binding::Key(left), Binding::Key(right)],
+            Binding::Buttons(left, right) => vec![Binding::Gamepad(left), Binding::Gamepad(right)],
+            binding => vec![binding],
+        }
+    }
+
+    pub fn name(&self, locale: &Locale) -> String {
+        match *self {
+            Binding::Key(key) => key_name(key),
+            Binding::Mouse(MouseButton::Left) => locale.text("binding.left_click"),
+            Binding::Mouse(MouseButton::Right) => locale.text("binding.right_click"),
+            Binding::Mouse(MouseButton::Middle) => locale.text("binding.middle_click"),
+            Binding::Mouse(MouseButton::Other(button)) => {
+                locale.format("binding.mouse", &[("button", &button)])
+            }
+            Binding::Touch => locale.text("binding.touch"),
+            Binding::Gamepad(button) => gamepad_button_name(button),
+            Binding::Pointer => locale.text("binding.pointer"),
+            Binding::Stick(Stick::Left) => locale.text("binding.left_stick"),
+            Binding::Stick(Stick::Right) => locale.text("binding.right_stick"),
+            Binding::Keys(left, right) => format!("{} / {}", key_name(left), key_name(right)),
+            Binding::Buttons(left, right) => format!(
+                "{} / {}",
+                gamepad_button_name(left),
+                gamepad_button_name(right)
+            ),
+        }
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Return => "Enter".to_string(),
+        KeyCode::Back => "Backspace".to_string(),
+        KeyCode::Escape => "Esc".to_string(),
+        key => format!("{:?}", key),
+    }
+}
+
+// xbox names, they're the ones printed on most pads
+fn gamepad_button_name(button: GamepadButtonType) -> String {
+    match button {
+        GamepadButtonType::South => "A".to_string(),
+        GamepadButtonType::East => "B".to_string(),
+        GamepadButtonType::West => "X".to_string(),
+        GamepadButtonType::North => "Y".to_string(),
+        GamepadButtonType::LeftTrigger => "LB".to_string(),
+        GamepadButtonType::RightTrigger => "RB".to_string(),
+        GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+        GamepadButtonType::RightTrigger2 => "RT".to_string(),
+        GamepadButtonType::Select => "Back".to_string(),
+        GamepadButtonType::DPadUp => "D-pad up".to_string(),
+        GamepadButtonType::DPadDown => "D-pad down".to_string(),
+        GamepadButtonType::DPadLeft => "D-pad left".to_string(),
+        GamepadButtonType::DPadRight => "D-pad right".to_string(),
+        button => format!("{:?}", button),
+    }
+}
+
+// what each action is bound to, saved with the rest of the settings
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bindings(pub BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use Binding::*;
+        use GamepadButtonType::*;
+
+        Bindings(BTreeMap::from([
+            (
+                Action::Aim,
+                vec![
+                    Pointer,
+                    Keys(KeyCode::Left, KeyCode::Right),
+                    Keys(KeyCode::A, KeyCode::D),
+                    Stick(self::Stick::Left),
+                    Buttons(DPadLeft, DPadRight),
+                ],
+            ),
+            (
+                Action::Charge,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Touch,
+                    Key(KeyCode::Space),
+                    Gamepad(RightTrigger2),
+                    Gamepad(South),
+                ],
+            ),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), Key(KeyCode::P), Gamepad(Start)],
+            ),
+            (Action::Restart, vec![Key(KeyCode::R), Gamepad(North)]),
+            (Action::Menu, vec![Key(KeyCode::M), Gamepad(Select)]),
+            (Action::Skip, vec![Key(KeyCode::Tab), Gamepad(East)]),
+            (
+                Action::Confirm,
+                vec![Key(KeyCode::Return), Key(KeyCode::Space), Gamepad(South)],
+            ),
+            (Action::Back, vec![Key(KeyCode::Back), Gamepad(East)]),
+            (Action::LevelSelect, vec![Key(KeyCode::L), Gamepad(North)]),
+            (
+                Action::Up,
+                vec![
+                    Key(KeyCode::Up),
+                    Key(KeyCode::W),
+                    Stick(self::Stick::Left),
+                    Gamepad(DPadUp),
+                ],
+            ),
+            (
+                Action::Down,
+                vec![
+                    Key(KeyCode::Down),
+                    Key(KeyCode::S),
+                    Stick(self::Stick::Left),
+                    Gamepad(DPadDown),
+                ],
+            ),
+            (
+                Action::Left,
+                vec![
+                    Key(KeyCode::Left),
+                    Key(KeyCode::A),
+                    Stick(self::Stick::Left),
+                    Gamepad(DPadLeft),
+                ],
+            ),
+            (
+                Action::Right,
+                vec![
+                    Key(KeyCode::Right),
+                    Key(KeyCode::D),
+                    Stick(self::Stick::Left),
+                    Gamepad(DPadRight),
+                ],
+            ),
+        ]))
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // actions added since the bindings were saved start out with their defaults
+    pub fn with_missing_defaults(mut self) -> Self {
+        for (action, bindings) in Bindings::default().0 {
+            self.0.entry(action).or_insert(bindings);
+        }
+        self
+    }
+
+    // swap out one column of an action's bindings
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|other| other.kind() != binding.kind() || other.is_fixed());
+        bindings.push(binding);
+    }
+
+    pub fn describe(&self, action: Action, kind: BindingKind, locale: &Locale) -> String {
+        self.get(action)
+            .iter()
+            .filter(|binding| binding.kind() == kind)
+            .map(|binding| binding.name(locale))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // every key or button that more than one action listens to at the same time.
+    // sticks, the pointer and touch can't be moved, each action reads them its own way
+    pub fn conflicts(&self) -> Vec<(Binding, Vec<Action>)> {
+        let mut users: Vec<(Binding, Context, Vec<Action>)> = Vec::new();
+        for (action, bindings) in self.0.iter() {
+            let context = action.context();
+            let inputs = bindings
+                .iter()
+                .filter(|binding| !binding.is_fixed())
+                .flat_map(Binding::inputs);
+            for input in inputs {
+                match users
+                    .iter_mut()
+                    .find(|(other, other_context, _)| *other == input && *other_context == context)
+                {
+                    Some((_, _, actions)) if !actions.contains(action) => actions.push(*action),
+                    Some(_) => {}
+                    None => users.push((input, context, vec![*action])),
+                }
+            }
+        }
+        users
+            .into_iter()
+            .filter(|(_, _, actions)| actions.len() > 1)
+            .map(|(input, _, actions)| (input, actions))
+            .collect()
+    }
+
+    pub fn is_conflicting(&self, binding: &Binding) -> bool {
+        let inputs = binding.inputs();
+        self.conflicts()
+            .iter()
+            .any(|(conflict, _)| inputs.contains(conflict))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum InputDevice {
+    #[default]
+    Pointer,
+    Keyboard,
+    Gamepad(Gamepad),
+}
+
+// the player's intent this frame, whichever device it came from
+#[derive(Resource, Default)]
+pub struct PlayerInput {
+    // where the cannon should point, None leaves it be
+    pub aim: Option<f32>,
+    held: BTreeSet<Action>,
+    previous: BTreeSet<Action>,
+    // whatever was touched last, for prompts and rumble
+    pub device: InputDevice,
+}
+
+impl PlayerInput {
+    fn set_held(&mut self, held: BTreeSet<Action>) {
+        self.previous = std::mem::replace(&mut self.held, held);
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.held.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed(action) && !self.previous.contains(&action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        !self.pressed(action) && self.previous.contains(&action)
+    }
+
+    pub fn charge_prompt(&self, bindings: &Bindings, locale: &Locale) -> String {
+        let kind = match self.device {
+            InputDevice::Pointer => BindingKind::Mouse,
+            InputDevice::Keyboard => BindingKind::Keyboard,
+            InputDevice::Gamepad(_) => BindingKind::Gamepad,
+        };
+        let binding = bindings
+            .get(Action::Charge)
+            .iter()
+            .find(|binding| binding.kind() == kind && !binding.is_fixed());
+
+        match binding {
+            Some(Binding::Mouse(MouseButton::Left)) => locale.text("hud.fire_with_mouse"),
+            Some(binding) => locale.format("hud.fire_with", &[("binding", &binding.name(locale))]),
+            None => locale.text("hud.fire"),
+        }
+    }
+}
+
+// cannon angle the stick points at, once it's pushed far enough
+fn stick_aim(stick: Vec2) -> Option<f32> {
+    (stick.length() > STICK_DEADZONE).then(|| Aim::from_direction(stick).0)
+}
+
+// the menu direction a stick is pushed in, whichever axis is further over
+fn stick_step(stick: Vec2) -> Option<Action> {
+    if stick.length() <= STICK_STEP_THRESHOLD {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(if stick.x < 0.0 {
+            Action::Left
+        } else {
+            Action::Right
+        })
+    } else {
+        Some(if stick.y < 0.0 {
+            Action::Down
+        } else {
+            Action::Up
+        })
+    }
+}
+
+fn read_player_input(
+    mut input: ResMut<PlayerInput>,
+    bindings: Res<Bindings>,
+    aim: Res<Aim>,
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut ev_cursor_moved: EventReader<CursorMoved>,
+    touches: Res<Touches>,
+    (gamepads, gamepad_input, gamepad_buttons, gamepad_axes): (
+        Res<Gamepads>,
+        Res<Input<GamepadButton>>,
+        Res<Axis<GamepadButton>>,
+        Res<Axis<GamepadAxis>>,
+    ),
+    q_earth: Query<&GlobalTransform, With<Earth>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    q_interaction: Query<&Interaction>,
+    mut pointer_blocked: Local<bool>,
+    listening: Option<Res<Listening>>,
+) {
+    // whichever device was used last decides prompts and rumble
+    if keyboard_input.get_just_pressed().next().is_some() {
+        input.device = InputDevice::Keyboard;
+    }
+    for gamepad in gamepads.iter() {
+        let pushed = [GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY]
+            .into_iter()
+            .any(|axis_type| {
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, axis_type))
+                    .is_some_and(|value| value.abs() > STICK_DEADZONE)
+            });
+        let pressed = gamepad_input
+            .get_just_pressed()
+            .any(|button| button.gamepad == gamepad);
+        if pushed || pressed {
+            input.device = InputDevice::Gamepad(gamepad);
+        }
+    }
+    // presses that land on a button belong to the button
+    if mouse_button_input.get_just_pressed().next().is_some() || touches.any_just_pressed() {
+        *pointer_blocked = pointer_over_ui(q_interaction);
+        input.device = InputDevice::Pointer;
+    }
+    if ev_cursor_moved.iter().count() > 0 {
+        input.device = InputDevice::Pointer;
+    }
+    // a second finger is pinching to zoom, so nothing launches until they're all lifted
+    if touches.iter().nth(1).is_some() {
+        *pointer_blocked = true;
+    }
+    let pointer_held =
+        mouse_button_input.get_pressed().next().is_some() || touches.iter().next().is_some();
+    if !pointer_held {
+        *pointer_blocked = false;
+    }
+
+    let pad_held = |button_type| {
+        gamepads.iter().any(|gamepad| {
+            let button = GamepadButton::new(gamepad, button_type);
+            // triggers are analog, count them as held a little way in
+            gamepad_input.pressed(button)
+                || gamepad_buttons
+                    .get(button)
+                    .is_some_and(|value| value > TRIGGER_THRESHOLD)
+        })
+    };
+    // the first pad with `side` pushed past the deadzone
+    let stick_at = |side: Stick| {
+        let (x, y) = match side {
+            Stick::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            Stick::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        };
+        gamepads
+            .iter()
+            .map(|gamepad| {
+                let axis = |axis_type| {
+                    gamepad_axes
+                        .get(GamepadAxis::new(gamepad, axis_type))
+                        .unwrap_or(0.0)
+                };
+                Vec2::new(axis(x), axis(y))
+            })
+            .find(|stick| stick.length() > STICK_DEADZONE)
+    };
+    let is_held = |action: Action, binding: &Binding| match *binding {
+        Binding::Key(key) => keyboard_input.pressed(key),
+        Binding::Mouse(button) => mouse_button_input.pressed(button) && !*pointer_blocked,
+        Binding::Touch => touches.iter().next().is_some() && !*pointer_blocked,
+        Binding::Gamepad(button) => pad_held(button),
+        Binding::Stick(side) => stick_at(side).and_then(stick_step) == Some(action),
+        // aiming isn't held, it points
+        _ => false,
+    };
+
+    let held = Action::ALL
+        .into_iter()
+        .filter(|action| {
+            bindings
+                .get(*action)
+                .iter()
+                .any(|binding| is_held(*action, binding))
+        })
+        .collect();
+    input.set_held(held);
+    // whatever is pressed while rebinding is the new binding, not a command
+    if listening.is_some() {
+        input.previous = input.held.clone();
+        input.aim = None;
+        return;
+    }
+
+    // turning with keys or buttons beats a stick, which beats the pointer
+    let mut turn = 0.0;
+    let mut stick = None;
+    let mut follow_pointer = false;
+    for binding in bindings.get(Action::Aim) {
+        match *binding {
+            Binding::Keys(left, right) => {
+                turn += keyboard_input.pressed(right) as i32 as f32;
+                turn -= keyboard_input.pressed(left) as i32 as f32;
+            }
+            Binding::Buttons(left, right) => {
+                turn += pad_held(right) as i32 as f32;
+                turn -= pad_held(left) as i32 as f32;
+            }
+            Binding::Stick(side) => stick = stick.or(stick_at(side).and_then(stick_aim)),
+            Binding::Pointer => follow_pointer = true,
+            _ => {}
+        }
+    }
+
+    input.aim = if turn != 0.0 {
+        Some(aim.0 + turn.signum() * KEYBOARD_AIM_SPEED * time.delta_seconds())
+    } else if stick.is_some() {
+        stick
+    } else if follow_pointer && input.device == InputDevice::Pointer {
+        pointer_aim(&q_window, &q_camera, &q_earth, &touches)
+    } else {
+        None
+    };
+}
+
+// the cannon points from earth towards the cursor or finger
+fn pointer_aim(
+    q_window: &Query<&Window, With<PrimaryWindow>>,
+    q_camera: &Query<(&Camera, &GlobalTransform)>,
+    q_earth: &Query<&GlobalTransform, With<Earth>>,
+    touches: &Touches,
+) -> Option<f32> {
+    let window = q_window.get_single().ok()?;
+    let (camera, camera_transform) = q_camera.get_single().ok()?;
+    let earth_transform = q_earth.get_single().ok()?;
+
+    let fallback_cursor_pos = Vec2::new(window.width() / 2.0, window.height() / 2.0);
+    let cursor = touches
+        .first_pressed_position()
+        .or_else(|| window.cursor_position())
+        .unwrap_or(fallback_cursor_pos);
+
+    let cursor_world_pos = camera
+        .viewport_to_world(camera_transform, cursor)?
+        .origin
+        .truncate();
+    let offset = cursor_world_pos - earth_transform.translation().xy();
+    (offset != Vec2::ZERO).then(|| Aim::from_direction(offset).0)
+}
+
+// the pad hums harder the more power is building up
+fn rumble_while_charging(
+    input: Res<PlayerInput>,
+    launch_power: Res<LaunchPower>,
+    mut ev_rumble: EventWriter<GamepadRumbleRequest>,
+    mut last_intensity: Local<f32>,
+) {
+    let InputDevice::Gamepad(gamepad) = input.device else {
+        return;
+    };
+
+    // full power is 2 seconds of charging, stepped so the motor isn't restarted every frame
+    let intensity = (launch_power.0.elapsed_secs() / 2.0 * 10.0).round() / 10.0 * 0.6;
+    if intensity == *last_intensity {
+        return;
+    }
+    *last_intensity = intensity;
+
+    ev_rumble.send(GamepadRumbleRequest::Stop { gamepad });
+    ev_rumble.send(GamepadRumbleRequest::Add {
+        gamepad,
+        intensity: GamepadRumbleIntensity::weak_motor(intensity),
+        duration: Duration::from_secs(3),
+    });
+}
+
+fn stop_rumble(input: Res<PlayerInput>, mut ev_rumble: EventWriter<GamepadRumbleRequest>) {
+    if let InputDevice::Gamepad(gamepad) = input.device {
+        ev_rumble.send(GamepadRumbleRequest::Stop { gamepad });
+    }
+}
+
+// one kick as the crate leaves, as strong as the shot
+fn rumble_on_launch(
+    input: Res<PlayerInput>,
+    mut ev_launched: EventReader<CrateLaunched>,
+    mut ev_rumble: EventWriter<GamepadRumbleRequest>,
+) {
+    for launched in ev_launched.iter() {
+        if let InputDevice::Gamepad(gamepad) = input.device {
+            ev_rumble.send(GamepadRumbleRequest::Add {
+                gamepad,
+                intensity: GamepadRumbleIntensity::strong_motor((launched.power / 2.0).min(1.0)),
+                duration: Duration::from_millis(200),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stick_needs_a_push_to_aim() {
+        assert_eq!(stick_aim(Vec2::new(0.1, 0.1)), None);
+        assert_eq!(stick_aim(Vec2::new(0.0, 1.0)), Some(0.0));
+        let right = stick_aim(Vec2::new(1.0, 0.0)).unwrap();
+        assert!((right - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn actions_press_and_release_once() {
+        let mut input = PlayerInput::default();
+        input.set_held(BTreeSet::from([Action::Charge]));
+        assert!(input.just_pressed(Action::Charge));
+        input.set_held(BTreeSet::from([Action::Charge]));
+        assert!(input.pressed(Action::Charge));
+        assert!(!input.just_pressed(Action::Charge) && !input.just_released(Action::Charge));
+        input.set_held(BTreeSet::new());
+        assert!(input.just_released(Action::Charge));
+        input.set_held(BTreeSet::new());
+        assert!(!input.just_released(Action::Charge));
+    }
+
+    #[test]
+    fn stick_steps_the_way_its_pushed_furthest() {
+        assert_eq!(stick_step(Vec2::new(0.4, 0.0)), None);
+        assert_eq!(stick_step(Vec2::new(0.3, 0.9)), Some(Action::Up));
+        assert_eq!(stick_step(Vec2::new(-0.9, 0.6)), Some(Action::Left));
+        assert_eq!(stick_step(Vec2::new(0.0, -1.0)), Some(Action::Down));
+    }
+
+    #[test]
+    fn default_bindings_dont_conflict() {
+        assert!(Bindings::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn menu_actions_conflict_among_themselves() {
+        // space charges in flight and confirms in menus, that's fine
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Back, Binding::Key(KeyCode::Space));
+        assert_eq!(
+            bindings.conflicts(),
+            vec![(
+                Binding::Key(KeyCode::Space),
+                vec![Action::Confirm, Action::Back]
+            )]
+        );
+    }
+
+    #[test]
+    fn shared_keys_are_conflicts() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Skip, Binding::Key(KeyCode::Space));
+        assert_eq!(
+            bindings.conflicts(),
+            vec![(
+                Binding::Key(KeyCode::Space),
+                vec![Action::Charge, Action::Skip]
+            )]
+        );
+
+        // one half of a turning pair is enough
+        bindings.rebind(Action::Skip, Binding::Key(KeyCode::A));
+        assert!(bindings.is_conflicting(&Binding::Keys(KeyCode::A, KeyCode::D)));
+    }
+
+    #[test]
+    fn rebinding_keeps_other_devices() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Charge, Binding::Key(KeyCode::C));
+        assert_eq!(
+            bindings.get(Action::Charge),
+            &[
+                Binding::Mouse(MouseButton::Left),
+                Binding::Touch,
+                Binding::Gamepad(GamepadButtonType::RightTrigger2),
+                Binding::Gamepad(GamepadButtonType::South),
+                Binding::Key(KeyCode::C),
+            ]
+        );
+
+        // the pointer keeps aiming when the turning keys change
+        bindings.rebind(Action::Aim, Binding::Keys(KeyCode::J, KeyCode::L));
+        assert!(bindings.get(Action::Aim).contains(&Binding::Pointer));
+        assert!(!bindings
+            .get(Action::Aim)
+            .contains(&Binding::Keys(KeyCode::A, KeyCode::D)));
+    }
+}
@@ -0,0 +1,268 @@
+use std::f32::consts::TAU;
+
+use bevy::{math::vec2, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    gameplay::{Level, EARTH_POSITION, EARTH_RADIUS, MERCURY_RADIUS, PICKUP_RADIUS, SUN_RADIUS},
+    level::{Around, AsteroidData, LevelData, Levels, Orbit},
+    locale::Locale,
+};
+
+pub struct EndlessPlugin;
+
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EndlessSeed(shared_seed().unwrap_or_else(random_seed)))
+            .add_systems(Startup, spawn_seed_text)
+            .add_systems(Update, update_seed_text);
+    }
+}
+
+// every endless level is generated from this and its depth, share it to share the run
+#[derive(Resource)]
+pub struct EndlessSeed(pub u64);
+
+impl EndlessSeed {
+    pub fn to_code(&self) -> String {
+        format!("{:X}", self.0)
+    }
+}
+
+// short enough to read out loud
+pub fn random_seed() -> u64 {
+    rand::random::<u32>() as u64
+}
+
+// a seed passed in as `--seed 1A2B3C` natively, or `?seed=1A2B3C` on the web
+pub fn shared_seed() -> Option<u64> {
+    let code = seed_argument()?;
+    match u64::from_str_radix(code.trim(), 16) {
+        Ok(seed) => Some(seed),
+        Err(e) => {
+            warn!("ignoring seed {:?}: {}", code, e);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn seed_argument() -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--seed");
+    args.next()?;
+    args.next()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn seed_argument() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("seed="))
+        .map(str::to_string)
+}
+
+// keep debris this far outside the sun and earth, so it can be collected without dying
+const DEBRIS_CLEARANCE: f32 = PICKUP_RADIUS + 1.0;
+// and this far apart, so no two are picked up by accident
+const DEBRIS_SPACING: f32 = PICKUP_RADIUS * 1.5;
+// room around the cannon for the crate to get going
+const LAUNCH_CLEARANCE: f32 = 9.0;
+// give up placing something after this many tries, levels just get a little emptier
+const PLACEMENT_ATTEMPTS: usize = 100;
+
+// the level `depth` levels past the end of the campaign
+pub fn generate(depth: usize, seed: u64) -> LevelData {
+    let mut rng = StdRng::seed_from_u64(seed ^ (depth as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let difficulty = depth as f32;
+
+    // the sun wanders a little further off center the deeper the run goes
+    let wander = difficulty.min(8.0);
+    let sun = vec2(
+        rng.gen_range(-wander..=wander),
+        15.0 + rng.gen_range(0.0..=wander * 0.5),
+    );
+
+    let mercury = generate_mercury_orbit(&mut rng, depth, sun);
+
+    // debris in a ring that widens with depth
+    let num_debris = (3 + depth).min(14);
+    let ring_inner = SUN_RADIUS + DEBRIS_CLEARANCE;
+    let ring_outer = (ring_inner + 6.0 + difficulty * 1.5).min(40.0);
+    let mut debris: Vec<Vec2> = Vec::new();
+    for _ in 0..num_debris {
+        let position = place(&mut rng, sun, ring_inner, ring_outer, |position| {
+            position.distance(EARTH_POSITION) > EARTH_RADIUS + DEBRIS_CLEARANCE
+                && debris
+                    .iter()
+                    .all(|other| other.distance(position) > DEBRIS_SPACING)
+        });
+        debris.extend(position);
+    }
+
+    // asteroids from the third endless level on, out where the crate has to fly
+    let num_asteroids = (depth.saturating_sub(1) / 2).min(5);
+    let mut asteroids: Vec<AsteroidData> = Vec::new();
+    for _ in 0..num_asteroids {
+        let radius = rng.gen_range(1.5..=2.5);
+        let placed = place(
+            &mut rng,
+            sun,
+            SUN_RADIUS + radius + 4.0,
+            ring_outer,
+            |position| {
+                position.distance(EARTH_POSITION) > EARTH_RADIUS + LAUNCH_CLEARANCE + radius
+                    && debris
+                        .iter()
+                        .all(|other| other.distance(position) > radius + DEBRIS_CLEARANCE)
+                    && asteroids
+                        .iter()
+                        .all(|other| other.position.distance(position) > radius + other.radius)
+            },
+        );
+        asteroids.extend(placed.map(|position| AsteroidData { position, radius }));
+    }
+
+    LevelData {
+        par: (debris.len() + 1) / 2 + asteroids.len() / 2,
+        sun,
+        mercury: Some(mercury),
+        debris,
+        moving_debris: Vec::new(),
+        asteroids,
+        bodies: Vec::new(),
+    }
+}
+
+// faster and more eccentric with depth, but never reaching earth or the sun
+fn generate_mercury_orbit(rng: &mut StdRng, depth: usize, sun: Vec2) -> Orbit {
+    let difficulty = depth as f32;
+    let closest = SUN_RADIUS + MERCURY_RADIUS + 2.0;
+    let furthest = sun.distance(EARTH_POSITION) - LAUNCH_CLEARANCE - MERCURY_RADIUS - 1.0;
+
+    let semi_major_axis = rng.gen_range(closest + 2.0..=furthest - 2.0);
+    let max_stretch = (semi_major_axis - closest).min(furthest - semi_major_axis);
+    let stretch = (rng.gen_range(2.0..=4.0) + difficulty * 0.5).min(max_stretch);
+    let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let speed = (0.4 + difficulty * 0.05).min(1.0);
+
+    Orbit {
+        around: Around::Sun,
+        semi_major_axis,
+        eccentricity: stretch / semi_major_axis,
+        period: direction * TAU / speed,
+        phase: rng.gen_range(0.0..TAU),
+    }
+}
+
+// random point in a ring around `center` that passes `fits`
+fn place(
+    rng: &mut StdRng,
+    center: Vec2,
+    inner: f32,
+    outer: f32,
+    fits: impl Fn(Vec2) -> bool,
+) -> Option<Vec2> {
+    (0..PLACEMENT_ATTEMPTS)
+        .map(|_| {
+            let angle = rng.gen_range(0.0..TAU);
+            let distance = rng.gen_range(inner..=outer);
+            center + vec2(angle.cos(), angle.sin()) * distance
+        })
+        .find(|position| fits(*position))
+}
+
+#[derive(Component)]
+struct SeedText;
+
+fn spawn_seed_text(mut commands: Commands) {
+    commands.spawn((
+        SeedText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE.with_a(0.6),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(80.0),
+            ..default()
+        }),
+    ));
+}
+
+// shown whenever an endless level is up, in the menu or in flight
+fn update_seed_text(
+    mut q_text: Query<(&mut Text, &mut Style), With<SeedText>>,
+    level: Res<Level>,
+    levels: Levels,
+    seed: Res<EndlessSeed>,
+    locale: Res<Locale>,
+) {
+    let display = if levels.is_endless(&level) {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    let value = locale.format("endless.seed", &[("seed", &seed.to_code())]);
+
+    for (mut text, mut style) in q_text.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_level() {
+        let a = generate(4, 0xC0FFEE);
+        let b = generate(4, 0xC0FFEE);
+        assert_eq!(a.sun, b.sun);
+        assert_eq!(a.debris, b.debris);
+        assert_eq!(a.mercury, b.mercury);
+    }
+
+    #[test]
+    fn levels_keep_clear_of_sun_and_earth() {
+        for seed in 0..50 {
+            for depth in 1..=20 {
+                let level = generate(depth, seed);
+                assert!(!level.debris.is_empty());
+
+                for debris in level.debris.iter() {
+                    assert!(debris.distance(level.sun) >= SUN_RADIUS + DEBRIS_CLEARANCE);
+                    assert!(debris.distance(EARTH_POSITION) > EARTH_RADIUS + DEBRIS_CLEARANCE);
+                }
+
+                for asteroid in level.asteroids.iter() {
+                    assert!(asteroid.position.distance(level.sun) > SUN_RADIUS + asteroid.radius);
+                    assert!(
+                        asteroid.position.distance(EARTH_POSITION)
+                            > EARTH_RADIUS + LAUNCH_CLEARANCE + asteroid.radius
+                    );
+                }
+
+                // mercury's whole orbit stays between the sun and earth
+                let orbit = level.mercury.unwrap();
+                let stretch = orbit.semi_major_axis * orbit.eccentricity;
+                assert!(orbit.semi_major_axis - stretch >= SUN_RADIUS + MERCURY_RADIUS - 1e-4);
+                assert!(
+                    orbit.semi_major_axis + stretch + MERCURY_RADIUS + EARTH_RADIUS
+                        < level.sun.distance(EARTH_POSITION)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,1786 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::{schedule::ScheduleLabel, system::SystemParam},
+    math::{vec2, vec3, Vec3Swizzles},
+    prelude::*,
+    time::Stopwatch,
+    transform::TransformPlugin,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cargo::CargoCatalog,
+    level::{Around, BodyData, LevelData, MovingDebris, Orbit},
+};
+
+// the simulation: no rendering, audio or windowing, so it also runs on MinimalPlugins
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TransformPlugin>() {
+            app.add_plugins(TransformPlugin);
+        }
+        if !app.is_plugin_added::<HierarchyPlugin>() {
+            app.add_plugins(HierarchyPlugin);
+        }
+
+        app.insert_resource(Score(0))
+            .insert_resource(Level(1))
+            .insert_resource(CurrentLevel(LevelData::default()))
+            .insert_resource(Cargo(CargoCatalog::default()))
+            .insert_resource(FixedTime::new_from_secs(1.0 / 60.0))
+            .insert_resource(LaunchPower(Stopwatch::new()))
+            .insert_resource(SimulationTick(0))
+            .insert_resource(Strokes(0))
+            .insert_resource(Aim(0.0))
+            .insert_resource(LevelSeed(rand::random()))
+            .insert_resource(GameRng(StdRng::seed_from_u64(0)))
+            .add_state::<GameState>()
+            .add_event::<CrateLaunched>()
+            .add_event::<DebrisCollected>()
+            .add_event::<CrateIncinerated>()
+            .add_event::<EarthDestroyed>()
+            .add_event::<CrateLostOnPlanet>()
+            .add_event::<LevelCleared>()
+            .add_systems(Startup, spawn_world)
+            .add_systems(
+                OnEnter(GameState::Menu),
+                (reroll_level_seed, reset_launch_power),
+            )
+            .add_systems(OnExit(GameState::Menu), spawn_level)
+            .add_systems(
+                RestartLevel,
+                (
+                    // the cannon lets go of a charge cut short
+                    reset_launch_power,
+                    despawn_crates,
+                    // debris stuck to them is gone before spawn_level clears the rest
+                    apply_deferred,
+                    spawn_level,
+                    // anywhere else, going back to ReadyToLaunch loads it
+                    spawn_crate.run_if(in_state(GameState::ReadyToLaunch)),
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::ReadyToLaunch), spawn_crate)
+            .add_systems(OnEnter(GameState::ChargingLaunch), reset_launch_power)
+            .add_systems(OnEnter(GameState::Launched), launch_crate)
+            .add_systems(
+                FixedUpdate,
+                (
+                    // state changes land on step boundaries, so they replay identically
+                    apply_state_transition::<GameState>,
+                    tick_simulation,
+                    orbit_bodies,
+                    move_debris,
+                    update_launch_power.run_if(in_state(GameState::ChargingLaunch)),
+                    // one step of crate flight: forces, then velocity, then position, then contacts
+                    (
+                        increase_crate_mass,
+                        apply_gravity,
+                        integrate_position,
+                        attach_debris_to_crate_collision,
+                        // debris collected on the way into the sun counts towards clearing
+                        apply_deferred,
+                        crash_crate_into_bodies,
+                        // what the crash means for the game, from the events alone
+                        advance_after_crash,
+                    )
+                        .chain()
+                        .run_if(in_state(GameState::Launched)),
+                )
+                    .chain()
+                    .in_set(Simulation),
+            )
+            .add_systems(Update, interpolate_transforms);
+    }
+}
+
+// everything advancing the world by one fixed step
+#[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Simulation;
+
+// run to start the level being played over, without going back to the menu
+#[derive(ScheduleLabel, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct RestartLevel;
+
+#[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Loading,
+    Menu,
+    ReadyToLaunch,
+    ChargingLaunch,
+    Launched,
+}
+
+#[derive(Resource)]
+pub struct Score(pub usize);
+
+// 1-based index into the campaign level list
+#[derive(Resource)]
+pub struct Level(pub usize);
+
+// layout of the level being played, spawned when leaving the menu
+#[derive(Resource)]
+pub struct CurrentLevel(pub LevelData);
+
+// what crates get filled with, from the cargo catalog once it has loaded
+#[derive(Resource)]
+pub struct Cargo(pub CargoCatalog);
+
+#[derive(Resource)]
+pub struct LaunchPower(pub Stopwatch);
+
+// fixed steps since the level was spawned, the clock everything simulated runs on
+#[derive(Resource)]
+pub struct SimulationTick(pub u32);
+
+// crates fired in the current attempt at the level
+#[derive(Resource)]
+pub struct Strokes(pub usize);
+
+// cannon angle around earth in radians, clockwise from straight up
+#[derive(Resource)]
+pub struct Aim(pub f32);
+
+impl Aim {
+    pub fn from_direction(direction: Vec2) -> Self {
+        Aim(direction.x.atan2(direction.y))
+    }
+
+    pub fn direction(&self) -> Vec2 {
+        vec2(self.0.sin(), self.0.cos())
+    }
+}
+
+// seeds GameRng when a level is spawned, rerolled every time the menu is shown
+#[derive(Resource)]
+pub struct LevelSeed(pub u64);
+
+// all randomness affecting a level comes from here
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+#[derive(Component)]
+pub struct Earth;
+
+#[derive(Component)]
+pub struct Mercury;
+
+#[derive(Component)]
+pub struct Debris;
+
+#[derive(Component)]
+pub struct OriginalTransform(pub Transform);
+
+// debris that opted in to moving, simulated while it's floating
+#[derive(Component, Clone, Copy)]
+pub struct Moving(pub MovingDebris);
+
+#[derive(Component)]
+pub struct Sun;
+
+// bodies that come with the level layout, replaced when the next one is spawned
+#[derive(Component)]
+pub struct LevelBody;
+
+// the scene a level body asked to be drawn with
+#[derive(Component)]
+pub struct BodyModel(pub String);
+
+// a body following `orbit` around `parent`
+#[derive(Component)]
+pub struct Orbiting {
+    pub parent: Entity,
+    pub orbit: Orbit,
+}
+
+// moons of moons of moons are fine, a body orbiting itself isn't
+const MAX_ORBIT_DEPTH: usize = 8;
+
+// the orbits from a body that stays put down to one orbiting it, enough to place it at any time
+#[derive(Clone, Debug)]
+pub struct OrbitPath {
+    root: Vec2,
+    // outermost first
+    orbits: Vec<Orbit>,
+}
+
+impl OrbitPath {
+    // follows the orbits from `ent` up to a body that stays put, placed by `root_position`
+    fn find(
+        ent: Entity,
+        q_orbiting: &Query<(Entity, &Orbiting)>,
+        root_position: impl Fn(Entity) -> Option<Vec2>,
+    ) -> Option<OrbitPath> {
+        let mut ent = ent;
+        let mut orbits = Vec::new();
+        while let Ok((_, orbiting)) = q_orbiting.get(ent) {
+            if orbits.len() >= MAX_ORBIT_DEPTH {
+                return None;
+            }
+            orbits.push(orbiting.orbit);
+            ent = orbiting.parent;
+        }
+        orbits.reverse();
+
+        Some(OrbitPath {
+            root: root_position(ent)?,
+            orbits,
+        })
+    }
+
+    // where the body is `elapsed` seconds into the level
+    pub fn position_at(&self, elapsed: f32) -> Vec2 {
+        self.orbits.iter().fold(self.root, |position, orbit| {
+            position + orbit.offset_at(elapsed)
+        })
+    }
+}
+
+// anything that pulls on crates and stops them when they get within `radius`
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CelestialBody {
+    pub kind: BodyKind,
+    pub gravity: f32,
+    pub radius: f32,
+    pub on_hit: OnHit,
+}
+
+// what a body is, to tell crashes apart
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BodyKind {
+    Sun,
+    Earth,
+    Mercury,
+    Asteroid,
+    // anything else a level brings, moons orbit another one of those
+    Planet,
+    Moon,
+}
+
+impl BodyKind {
+    pub fn key(&self) -> &'static str {
+        match self {
+            BodyKind::Sun => "body.sun",
+            BodyKind::Earth => "body.earth",
+            BodyKind::Mercury => "body.mercury",
+            BodyKind::Asteroid => "body.asteroid",
+            BodyKind::Planet => "body.planet",
+            BodyKind::Moon => "body.moon",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Cannon;
+
+#[derive(Component)]
+pub struct Crate(pub String);
+
+// looped for as long as the crate is around, the asset path from its cargo
+#[derive(Component)]
+pub struct CargoSound(pub String);
+
+#[derive(Component)]
+pub struct CurrentCrate;
+
+// simulated position, only written in FixedUpdate.
+// Transform is interpolated between the last two steps so rendering stays smooth
+#[derive(Component)]
+pub struct Position {
+    pub previous: Vec2,
+    pub current: Vec2,
+}
+
+impl Position {
+    pub fn new(position: Vec2) -> Self {
+        Position {
+            previous: position,
+            current: position,
+        }
+    }
+
+    pub fn step_to(&mut self, position: Vec2) {
+        self.previous = self.current;
+        self.current = position;
+    }
+}
+
+// simulated orientation, interpolated into Transform the same way as Position
+#[derive(Component)]
+pub struct Rotation {
+    pub previous: Quat,
+    pub current: Quat,
+}
+
+impl Rotation {
+    pub fn new(rotation: Quat) -> Self {
+        Rotation {
+            previous: rotation,
+            current: rotation,
+        }
+    }
+
+    pub fn step_to(&mut self, rotation: Quat) {
+        self.previous = self.current;
+        self.current = rotation;
+    }
+}
+
+#[derive(Component)]
+pub struct Velocity(pub Vec2);
+
+// spin around the z axis, in radians per second
+#[derive(Component)]
+pub struct AngularVelocity(pub f32);
+
+#[derive(Component)]
+pub struct Mass(pub f32);
+
+// what the simulation moves a crate with once it's out of the cannon.
+// Mass comes with the crate, the trajectory preview needs it before launch
+#[derive(Bundle)]
+pub struct RigidBody {
+    pub position: Position,
+    pub rotation: Rotation,
+    pub velocity: Velocity,
+    pub angular_velocity: AngularVelocity,
+}
+
+#[derive(Component)]
+pub struct PickedUp;
+
+#[derive(Event)]
+pub struct CrateLaunched {
+    pub power: f32,
+    pub aim: f32,
+    pub tick: u32,
+}
+
+#[derive(Event)]
+pub struct DebrisCollected;
+
+// every crash comes with the crate's name, the body it hit and where it touched it
+#[derive(Event)]
+pub struct CrateIncinerated {
+    pub name: String,
+    pub body: BodyKind,
+    pub position: Vec2,
+}
+
+#[derive(Event)]
+pub struct EarthDestroyed {
+    pub name: String,
+    pub body: BodyKind,
+    pub position: Vec2,
+}
+
+#[derive(Event)]
+pub struct CrateLostOnPlanet {
+    pub name: String,
+    pub body: BodyKind,
+    pub position: Vec2,
+}
+
+// all three kinds of crash, for anything that treats them alike
+#[derive(SystemParam)]
+pub struct CrashEvents<'w, 's> {
+    incinerated: EventReader<'w, 's, CrateIncinerated>,
+    earth_destroyed: EventReader<'w, 's, EarthDestroyed>,
+    lost: EventReader<'w, 's, CrateLostOnPlanet>,
+}
+
+impl<'w, 's> CrashEvents<'w, 's> {
+    pub fn iter(&mut self) -> impl Iterator<Item = (OnHit, Vec2)> + '_ {
+        let incinerated = self
+            .incinerated
+            .iter()
+            .map(|crash| (OnHit::Incinerate, crash.position));
+        let earth_destroyed = self
+            .earth_destroyed
+            .iter()
+            .map(|crash| (OnHit::DestroyEarth, crash.position));
+        let lost = self
+            .lost
+            .iter()
+            .map(|crash| (OnHit::SmashCrate, crash.position));
+        incinerated.chain(earth_destroyed).chain(lost)
+    }
+}
+
+// what happens to a crate that hits a body
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OnHit {
+    // burnt up, clears the level once all debris is gone
+    Incinerate,
+    // game over
+    DestroyEarth,
+    // the crate is lost and its debris goes back where it was
+    SmashCrate,
+}
+
+#[derive(Event)]
+pub struct LevelCleared {
+    pub level: usize,
+    pub strokes: usize,
+}
+
+fn spawn_world(mut commands: Commands) {
+    // spawn sun
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_translation(vec3(0.0, 15.0, -50.0))
+                .with_rotation(Quat::from_rotation_x(PI / 2.0)),
+        ),
+        Position::new(vec2(0.0, 15.0)),
+        Sun,
+        CelestialBody {
+            kind: BodyKind::Sun,
+            gravity: SUN_GRAVITY,
+            radius: SUN_RADIUS,
+            on_hit: OnHit::Incinerate,
+        },
+    ));
+
+    // spawn earth
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_translation(EARTH_POSITION.extend(-20.0))
+                .with_scale(Vec3::splat(5.0))
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
+        ),
+        Position::new(EARTH_POSITION),
+        Earth,
+        CelestialBody {
+            kind: BodyKind::Earth,
+            gravity: EARTH_GRAVITY,
+            radius: EARTH_RADIUS,
+            on_hit: OnHit::DestroyEarth,
+        },
+    ));
+
+    // spawn cannon
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(0.0, 0.0, 0.0)
+                .with_scale(Vec3::splat(1.0))
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
+        ),
+        Cannon,
+    ));
+}
+
+fn spawn_level(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut tick: ResMut<SimulationTick>,
+    mut strokes: ResMut<Strokes>,
+    seed: Res<LevelSeed>,
+    mut rng: ResMut<GameRng>,
+    mut q_sun: Query<(Entity, &mut Position), With<Sun>>,
+    q_earth: Query<Entity, With<Earth>>,
+    q_debris: Query<Entity, With<Debris>>,
+    q_level_bodies: Query<Entity, With<LevelBody>>,
+) {
+    let level_data = &current_level.0;
+
+    // restart the clock and rng, so every attempt at a level plays out the same
+    tick.0 = 0;
+    strokes.0 = 0;
+    rng.0 = StdRng::seed_from_u64(seed.0);
+
+    // move sun into place
+    let (sun_ent, mut sun_position) = q_sun.single_mut();
+    *sun_position = Position::new(level_data.sun);
+    let earth_ent = q_earth.single();
+
+    // despawn all existing debris
+    for debris_ent in q_debris.iter() {
+        commands.entity(debris_ent).despawn_recursive();
+    }
+
+    // spawn debris
+    let still = level_data.debris.iter().map(|position| (*position, None));
+    let moving = level_data
+        .moving_debris
+        .iter()
+        .map(|moving| (moving.position, Some(Moving(*moving))));
+    for (position, moving) in still.chain(moving) {
+        let x = position.x;
+        let y = position.y - level_data.sun.y;
+
+        let transform = Transform::from_xyz(position.x, position.y, 0.0)
+            .with_scale(Vec3::splat(2.0))
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0 + x, 0.0 + y * 2.0, 0.0));
+
+        spawn_debris(&mut commands, transform, moving);
+    }
+
+    // replace mercury, asteroids and whatever else the level brings
+    for body_ent in q_level_bodies.iter() {
+        commands.entity(body_ent).despawn_recursive();
+    }
+
+    // where each body starts, and what it orbits.
+    // bodies can only orbit ones listed before them, `spawned` holds those so far
+    let place = |body: &BodyData, spawned: &[(Entity, Vec2)]| {
+        let Some(orbit) = body.orbit else {
+            return (body.position, None);
+        };
+        let parent = match orbit.around {
+            Around::Sun => Some((sun_ent, level_data.sun)),
+            Around::Earth => Some((earth_ent, EARTH_POSITION)),
+            Around::Body(index) => spawned.get(index).copied(),
+        };
+        match parent {
+            Some((parent, parent_pos)) => (
+                parent_pos + orbit.offset_at(0.0),
+                Some(Orbiting { parent, orbit }),
+            ),
+            None => {
+                warn!(
+                    "{:?} isn't a body listed before the one orbiting it",
+                    orbit.around
+                );
+                (body.position, None)
+            }
+        }
+    };
+
+    if let Some(orbit) = level_data.mercury {
+        let mercury = BodyData {
+            position: Vec2::ZERO,
+            radius: MERCURY_RADIUS,
+            gravity: MERCURY_GRAVITY,
+            on_hit: OnHit::SmashCrate,
+            orbit: Some(orbit),
+            model: None,
+        };
+        let (position, orbiting) = place(&mercury, &[]);
+        let ent = spawn_body(
+            &mut commands,
+            &mercury,
+            BodyKind::Mercury,
+            position,
+            orbiting,
+        );
+        commands.entity(ent).insert(Mercury);
+    }
+
+    // asteroids don't pull, they're just in the way
+    for asteroid in level_data.asteroids.iter() {
+        let asteroid = BodyData {
+            position: asteroid.position,
+            radius: asteroid.radius,
+            gravity: 0.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: None,
+            model: None,
+        };
+        spawn_body(
+            &mut commands,
+            &asteroid,
+            BodyKind::Asteroid,
+            asteroid.position,
+            None,
+        );
+    }
+
+    let mut spawned = Vec::new();
+    for body in level_data.bodies.iter() {
+        let (position, orbiting) = place(body, &spawned);
+        let kind = match body.orbit.map(|orbit| orbit.around) {
+            Some(Around::Body(_)) => BodyKind::Moon,
+            _ => BodyKind::Planet,
+        };
+        let ent = spawn_body(&mut commands, body, kind, position, orbiting);
+        spawned.push((ent, position));
+    }
+}
+
+fn spawn_body(
+    commands: &mut Commands,
+    body: &BodyData,
+    kind: BodyKind,
+    position: Vec2,
+    orbiting: Option<Orbiting>,
+) -> Entity {
+    let mut ent = commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_translation(position.extend(0.0))
+                .with_scale(Vec3::splat(body.radius))
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, position.x, position.y, 0.0)),
+        ),
+        Position::new(position),
+        CelestialBody {
+            kind,
+            gravity: body.gravity,
+            radius: body.radius,
+            on_hit: body.on_hit,
+        },
+        LevelBody,
+    ));
+    if let Some(orbiting) = orbiting {
+        ent.insert(orbiting);
+    }
+    if let Some(model) = &body.model {
+        ent.insert(BodyModel(model.clone()));
+    }
+    ent.id()
+}
+
+fn spawn_debris(commands: &mut Commands, transform: Transform, moving: Option<Moving>) {
+    let mut ent = commands.spawn((
+        OriginalTransform(transform),
+        Debris,
+        TransformBundle::from_transform(transform),
+    ));
+    if let Some(moving) = moving {
+        ent.insert((moving, Position::new(transform.translation.xy())));
+    }
+}
+
+fn despawn_crates(mut commands: Commands, q_crates: Query<Entity, With<Crate>>) {
+    for ent in q_crates.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn reroll_level_seed(mut seed: ResMut<LevelSeed>) {
+    seed.0 = rand::random();
+}
+
+fn spawn_crate(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    cargo: Res<Cargo>,
+    level: Res<Level>,
+    q_cannon: Query<Entity, With<Cannon>>,
+) {
+    // things that humanity fires into the sun
+    let cargo = cargo.0.pick(level.0, &mut rng.0);
+
+    // spawn crate in cannon
+    for cannon_ent in q_cannon.iter() {
+        commands.entity(cannon_ent).with_children(|parent| {
+            let mut ent = parent.spawn((
+                TransformBundle::from_transform(
+                    Transform::from_xyz(0.0, 3.0, 0.0)
+                        .with_scale(Vec3::splat(1.0))
+                        .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
+                ),
+                Crate(cargo.name.clone()),
+                Mass(0.5 * cargo.mass),
+                CurrentCrate,
+            ));
+            if let Some(sound) = &cargo.sound {
+                ent.insert(CargoSound(sound.clone()));
+            }
+        });
+    }
+}
+
+fn tick_simulation(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+// orbits are worked out from the clock, not stepped, so they never drift
+fn orbit_bodies(
+    fixed_time: Res<FixedTime>,
+    tick: Res<SimulationTick>,
+    q_orbiting: Query<(Entity, &Orbiting)>,
+    mut q_bodies: Query<(&mut Position, &mut Transform), With<CelestialBody>>,
+) {
+    let elapsed = tick.0 as f32 * fixed_time.period.as_secs_f32();
+
+    // every position first, a moon needs where its planet is now, not a step ago
+    let root_position = |ent| q_bodies.get(ent).ok().map(|(position, _)| position.current);
+    let positions = q_orbiting
+        .iter()
+        .filter_map(|(ent, _)| {
+            let path = OrbitPath::find(ent, &q_orbiting, root_position)?;
+            Some((ent, path.position_at(elapsed)))
+        })
+        .collect::<Vec<_>>();
+
+    for (ent, new_position) in positions {
+        if let Ok((mut position, mut transform)) = q_bodies.get_mut(ent) {
+            position.step_to(new_position);
+            transform.rotation = Quat::from_euler(EulerRot::XYZ, 0.7, elapsed * 0.2, elapsed);
+        }
+    }
+}
+
+// moving debris follows the clock like orbits do. it's kept out of reach of anything
+// that incinerates, or every crate burning up nearby would pick it up for free
+fn move_debris(
+    fixed_time: Res<FixedTime>,
+    tick: Res<SimulationTick>,
+    mut q_debris: Query<(&mut Position, &Moving), (With<Debris>, Without<PickedUp>)>,
+    q_bodies: Query<(&Position, &CelestialBody), Without<Debris>>,
+    q_sun: Query<&Position, (With<Sun>, Without<Debris>)>,
+) {
+    let elapsed = tick.0 as f32 * fixed_time.period.as_secs_f32();
+    let Ok(sun) = q_sun.get_single() else {
+        return;
+    };
+
+    for (mut position, moving) in q_debris.iter_mut() {
+        let mut new_position = moving
+            .0
+            .motion
+            .position_at(moving.0.position, sun.current, elapsed);
+
+        for (body_position, body) in q_bodies.iter() {
+            if body.on_hit != OnHit::Incinerate {
+                continue;
+            }
+            let reach = body.radius + PICKUP_RADIUS;
+            let offset = new_position - body_position.current;
+            if offset.length() < reach {
+                new_position = body_position.current + offset.normalize_or_zero() * reach;
+            }
+        }
+
+        // debris put back after a smashed crate jumps straight to where it should be
+        if position.is_added() {
+            *position = Position::new(new_position);
+        } else {
+            position.step_to(new_position);
+        }
+    }
+}
+
+fn increase_crate_mass(mut q_crate: Query<&mut Mass, With<Crate>>, fixed_time: Res<FixedTime>) {
+    for mut mass in q_crate.iter_mut() {
+        mass.0 += fixed_time.period.as_secs_f32() * MASS_GROWTH;
+    }
+}
+
+fn update_launch_power(
+    mut launch_power: ResMut<LaunchPower>,
+    fixed_time: Res<FixedTime>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    launch_power.0.tick(fixed_time.period);
+
+    // if launch_power > 1.0, go to Launched
+    if launch_power.0.elapsed_secs() > 2.0 {
+        next_state.set(GameState::Launched);
+    }
+}
+
+fn reset_launch_power(mut launch_power: ResMut<LaunchPower>) {
+    launch_power.0.reset();
+}
+
+fn launch_crate(
+    mut commands: Commands,
+    mut launch_power: ResMut<LaunchPower>,
+    aim: Res<Aim>,
+    tick: Res<SimulationTick>,
+    mut current_crate: Query<
+        (Entity, &mut Transform),
+        (With<CurrentCrate>, Without<Cannon>, Without<Earth>),
+    >,
+    cannon: Query<&Transform, (With<Cannon>, Without<Earth>)>,
+    earth: Query<&Transform, (With<Earth>, Without<Cannon>)>,
+    mut score: ResMut<Score>,
+    mut strokes: ResMut<Strokes>,
+    mut ev_launched: EventWriter<CrateLaunched>,
+) {
+    let power = launch_power.0.elapsed_secs();
+
+    ev_launched.send(CrateLaunched {
+        power,
+        aim: aim.0,
+        tick: tick.0,
+    });
+
+    let (crate_ent, mut crate_transform) = current_crate.single_mut();
+    let cannon_transform = *cannon.single();
+    let earth_transform = *earth.single();
+    let start = launch_position(&earth_transform, &aim);
+
+    // place crate at the muzzle
+    crate_transform.translation = start;
+    crate_transform.rotation = cannon_transform.rotation * crate_transform.rotation;
+
+    // from here on the simulation moves it
+    commands.entity(crate_ent).insert(RigidBody {
+        position: Position::new(start.xy()),
+        rotation: Rotation::new(crate_transform.rotation),
+        velocity: Velocity(launch_velocity(&aim, power)),
+        angular_velocity: AngularVelocity(CRATE_SPIN),
+    });
+
+    // move current_crate from parent to root
+    commands.entity(crate_ent).remove::<Parent>();
+
+    // reset launch_power
+    launch_power.0.reset();
+
+    // increase score
+    score.0 += 1;
+    strokes.0 += 1;
+}
+
+// gravity well strength of each body
+const SUN_GRAVITY: f32 = 100.0;
+const EARTH_GRAVITY: f32 = 3.0;
+const MERCURY_GRAVITY: f32 = 3.0;
+
+// crates burn up or crash within these distances
+pub const SUN_RADIUS: f32 = 13.2;
+pub const EARTH_RADIUS: f32 = 5.0;
+pub const MERCURY_RADIUS: f32 = 3.0;
+
+// crates collect debris within this distance
+pub const PICKUP_RADIUS: f32 = 3.7;
+
+pub const EARTH_POSITION: Vec2 = Vec2::new(0.0, -25.0);
+
+// mass gained per second of flight
+const MASS_GROWTH: f32 = 0.35;
+
+// position units moved per second, per unit of velocity
+pub const VELOCITY_SCALE: f32 = 20.0;
+
+// radians per second a crate tumbles at once it leaves the cannon
+pub const CRATE_SPIN: f32 = 2.0;
+
+// a body pulling on crates in flight
+#[derive(Clone, Debug)]
+pub struct Attractor {
+    pub position: Vec3,
+    pub strength: f32,
+    pub radius: f32,
+    // none for bodies that stay put
+    pub orbit: Option<OrbitPath>,
+}
+
+impl Attractor {
+    // velocity change of a crate at `crate_pos` over one fixed step
+    pub fn pull(&self, crate_pos: Vec3, mass: f32) -> Vec2 {
+        self.pull_from(self.position, crate_pos, mass)
+    }
+
+    // same as `pull`, with the body at `position` instead of where it is now.
+    // bodies sit at different depths, which softens their pull
+    pub fn pull_from(&self, position: Vec3, crate_pos: Vec3, mass: f32) -> Vec2 {
+        let distance = position.distance(crate_pos);
+        let direction = (position - crate_pos).normalize();
+        let gravity = (direction * self.strength * mass) / distance.powi(2);
+        gravity.xy()
+    }
+
+    // where the body is `elapsed` seconds into the level, the way orbit_bodies puts it there
+    pub fn position_at(&self, elapsed: f32) -> Vec3 {
+        match &self.orbit {
+            Some(orbit) => orbit.position_at(elapsed).extend(self.position.z),
+            None => self.position,
+        }
+    }
+}
+
+// how far through a step, from 0 to 1, something moving from `from` to `to` first gets
+// within `radius` of a target moving from `target_from` to `target_to` in the same step.
+// checking the whole segment means nothing is skipped over, however fast it goes
+pub fn time_of_impact(
+    from: Vec2,
+    to: Vec2,
+    target_from: Vec2,
+    target_to: Vec2,
+    radius: f32,
+) -> Option<f32> {
+    // solve |offset + t * motion| = radius, with the target standing still
+    let offset = from - target_from;
+    let motion = (to - from) - (target_to - target_from);
+
+    let c = offset.length_squared() - radius * radius;
+    if c < 0.0 {
+        return Some(0.0);
+    }
+    let a = motion.length_squared();
+    if a == 0.0 {
+        return None;
+    }
+    let b = 2.0 * offset.dot(motion);
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+// the first body a crate ran into during the last step, and how far into the step
+fn first_impact(
+    crate_position: &Position,
+    q_bodies: &Query<(&Position, &CelestialBody), Without<Crate>>,
+) -> Option<(f32, CelestialBody)> {
+    q_bodies
+        .iter()
+        .filter_map(|(position, body)| {
+            let t = time_of_impact(
+                crate_position.previous,
+                crate_position.current,
+                position.previous,
+                position.current,
+                body.radius,
+            )?;
+            Some((t, *body))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+}
+
+#[derive(SystemParam)]
+pub struct Attractors<'w, 's> {
+    q_bodies: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Position,
+            &'static Transform,
+            &'static CelestialBody,
+        ),
+        Without<Crate>,
+    >,
+    q_orbiting: Query<'w, 's, (Entity, &'static Orbiting)>,
+}
+
+impl<'w, 's> Attractors<'w, 's> {
+    pub fn all(&self) -> Vec<Attractor> {
+        let root_position = |ent| {
+            self.q_bodies
+                .get(ent)
+                .ok()
+                .map(|(_, position, _, _)| position.current)
+        };
+
+        self.q_bodies
+            .iter()
+            .map(|(ent, position, transform, body)| Attractor {
+                position: position.current.extend(transform.translation.z),
+                strength: body.gravity,
+                radius: body.radius,
+                orbit: self
+                    .q_orbiting
+                    .contains(ent)
+                    .then(|| OrbitPath::find(ent, &self.q_orbiting, root_position))
+                    .flatten(),
+            })
+            .collect()
+    }
+}
+
+// distance from earth's center to the cannon, and from the cannon to its muzzle
+pub const CANNON_RADIUS: f32 = 6.0;
+const MUZZLE_LENGTH: f32 = 3.0;
+
+// where a crate leaves the cannon
+pub fn launch_position(earth: &Transform, aim: &Aim) -> Vec3 {
+    earth.translation + (aim.direction() * (CANNON_RADIUS + MUZZLE_LENGTH)).extend(0.0)
+}
+
+// velocity given to a crate leaving the cannon
+pub fn launch_velocity(aim: &Aim, launch_power: f32) -> Vec2 {
+    aim.direction() * launch_power * 1.5
+}
+
+// where a crate launched after simulation tick `tick` would fly, one position per fixed
+// step, following the same steps as the FixedUpdate pipeline. attractors move along their
+// orbits and debris pickups are ignored, stops early when hitting a body
+pub fn predict_flight(
+    start: Vec3,
+    mut velocity: Vec2,
+    mut mass: f32,
+    attractors: &[Attractor],
+    tick: u32,
+    dt: f32,
+    steps: usize,
+) -> Vec<Vec2> {
+    let mut position = start.xy();
+    let mut path = Vec::with_capacity(steps);
+
+    let bodies_at = |step: usize| {
+        let elapsed = (tick + step as u32) as f32 * dt;
+        attractors
+            .iter()
+            .map(|attractor| attractor.position_at(elapsed))
+            .collect::<Vec<_>>()
+    };
+    let mut previous_bodies = bodies_at(0);
+
+    for step in 1..=steps {
+        let bodies = bodies_at(step);
+
+        mass += dt * MASS_GROWTH;
+        for (attractor, body) in attractors.iter().zip(&bodies) {
+            velocity += attractor.pull_from(*body, position.extend(start.z), mass);
+        }
+        let previous = position;
+        position += velocity * dt * VELOCITY_SCALE;
+        path.push(position);
+
+        let hit = attractors
+            .iter()
+            .zip(previous_bodies.iter().zip(&bodies))
+            .any(|(attractor, (body_before, body))| {
+                time_of_impact(
+                    previous,
+                    position,
+                    body_before.xy(),
+                    body.xy(),
+                    attractor.radius,
+                )
+                .is_some()
+            });
+        if hit {
+            break;
+        }
+        previous_bodies = bodies;
+    }
+
+    path
+}
+
+fn apply_gravity(
+    mut q_crate: Query<(&mut Velocity, &Position, &Transform, &Mass), With<Crate>>,
+    attractors: Attractors,
+) {
+    let attractors = attractors.all();
+
+    for (mut velocity, crate_position, crate_transform, mass) in q_crate.iter_mut() {
+        let crate_pos = crate_position.current.extend(crate_transform.translation.z);
+
+        for attractor in attractors.iter() {
+            velocity.0 += attractor.pull(crate_pos, mass.0);
+        }
+    }
+}
+
+// semi-implicit euler: velocity was updated first, so position uses the new velocity
+fn integrate_position(
+    mut q_crate: Query<(&mut Position, &mut Rotation, &Velocity, &AngularVelocity), With<Crate>>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (mut position, mut rotation, velocity, angular_velocity) in q_crate.iter_mut() {
+        let new_position = position.current + velocity.0 * dt * VELOCITY_SCALE;
+        position.step_to(new_position);
+
+        let new_rotation = Quat::from_rotation_z(angular_velocity.0 * dt) * rotation.current;
+        rotation.step_to(new_rotation);
+    }
+}
+
+// place everything simulated between its last two steps
+fn interpolate_transforms(
+    fixed_time: Res<FixedTime>,
+    mut q_simulated: Query<(&mut Transform, &Position, Option<&Rotation>)>,
+) {
+    let alpha = (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).min(1.0);
+
+    for (mut transform, position, rotation) in q_simulated.iter_mut() {
+        let interpolated = position.previous.lerp(position.current, alpha);
+        transform.translation.x = interpolated.x;
+        transform.translation.y = interpolated.y;
+
+        if let Some(rotation) = rotation {
+            transform.rotation = rotation.previous.slerp(rotation.current, alpha);
+        }
+    }
+}
+
+fn attach_debris_to_crate_collision(
+    mut commands: Commands,
+    mut q_crate: Query<(Entity, &Position, &Rotation, &Transform, &mut Mass), With<CurrentCrate>>,
+    mut q_debris: Query<
+        (Entity, &mut Transform, Option<&Position>),
+        (With<Debris>, Without<PickedUp>, Without<CurrentCrate>),
+    >,
+    q_bodies: Query<(&Position, &CelestialBody), Without<Crate>>,
+    mut ev_picked_up: EventWriter<DebrisCollected>,
+) {
+    for (crate_ent, crate_position, crate_rotation, crate_transform, mut crate_mass) in
+        q_crate.iter_mut()
+    {
+        // nothing past whatever the crate crashed into this step
+        let reach = first_impact(crate_position, &q_bodies).map_or(1.0, |(t, _)| t);
+
+        for (debris_ent, mut debris_transform, debris_position) in q_debris.iter_mut() {
+            // moving debris is where the simulation has it, not where it's drawn
+            let (debris_from, debris_pos) = match debris_position {
+                Some(position) => (position.previous, position.current),
+                None => (
+                    debris_transform.translation.xy(),
+                    debris_transform.translation.xy(),
+                ),
+            };
+            let crate_pos = crate_position.current;
+
+            let hit = time_of_impact(
+                crate_position.previous,
+                crate_pos,
+                debris_from,
+                debris_pos,
+                PICKUP_RADIUS,
+            );
+            if hit.is_some_and(|t| t <= reach) {
+                // attach debris to crate, it rides along from now on
+                commands.entity(crate_ent).add_child(debris_ent);
+                commands
+                    .entity(debris_ent)
+                    .insert(PickedUp)
+                    .remove::<Position>();
+
+                let diff = debris_pos.extend(debris_transform.translation.z)
+                    - crate_pos.extend(crate_transform.translation.z);
+
+                // new debris pos = diff transformed by the simulated crate rotation
+                let new_debris_pos = crate_rotation.current.inverse() * diff;
+                debris_transform.translation = new_debris_pos * 0.8;
+
+                // increase crate mass
+                crate_mass.0 += 0.22;
+
+                ev_picked_up.send(DebrisCollected);
+            }
+        }
+    }
+}
+
+// the crash itself: the crate is gone, and a smashed one drops what it carried back where
+// it was found. what that means for the game is left to whoever listens
+fn crash_crate_into_bodies(
+    mut commands: Commands,
+    q_crate: Query<(Entity, &Crate, &Position)>,
+    q_bodies: Query<(&Position, &CelestialBody), Without<Crate>>,
+    q_picked_up_debris: Query<
+        (&OriginalTransform, Option<&Moving>),
+        (With<Debris>, With<PickedUp>),
+    >,
+    mut ev_incinerated: EventWriter<CrateIncinerated>,
+    mut ev_earth_destroyed: EventWriter<EarthDestroyed>,
+    mut ev_lost: EventWriter<CrateLostOnPlanet>,
+) {
+    for (crate_ent, crate_str, crate_position) in q_crate.iter() {
+        let Some((t, body)) = first_impact(crate_position, &q_bodies) else {
+            continue;
+        };
+
+        commands.entity(crate_ent).despawn_recursive();
+
+        // effects go where it touched, not wherever the step happened to end
+        let name = crate_str.0.clone();
+        let kind = body.kind;
+        let position = crate_position.previous.lerp(crate_position.current, t);
+        match body.on_hit {
+            OnHit::Incinerate => ev_incinerated.send(CrateIncinerated {
+                name,
+                body: kind,
+                position,
+            }),
+            OnHit::DestroyEarth => ev_earth_destroyed.send(EarthDestroyed {
+                name,
+                body: kind,
+                position,
+            }),
+            OnHit::SmashCrate => {
+                for (original_transform, moving) in q_picked_up_debris.iter() {
+                    spawn_debris(&mut commands, original_transform.0, moving.copied());
+                }
+                ev_lost.send(CrateLostOnPlanet {
+                    name,
+                    body: kind,
+                    position,
+                });
+            }
+        }
+    }
+}
+
+// on to the next level once every piece of debris has been burnt, or back to the menu
+// after destroying earth. anything else is another try at the same level
+fn advance_after_crash(
+    mut ev_incinerated: EventReader<CrateIncinerated>,
+    mut ev_earth_destroyed: EventReader<EarthDestroyed>,
+    mut ev_lost: EventReader<CrateLostOnPlanet>,
+    q_floating_debris: Query<Entity, (With<Debris>, Without<PickedUp>)>,
+    mut level: ResMut<Level>,
+    strokes: Res<Strokes>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ev_cleared: EventWriter<LevelCleared>,
+) {
+    for _ in ev_incinerated.iter() {
+        if q_floating_debris.is_empty() {
+            ev_cleared.send(LevelCleared {
+                level: level.0,
+                strokes: strokes.0,
+            });
+            level.0 += 1;
+            next_state.set(GameState::Menu);
+        } else {
+            next_state.set(GameState::ReadyToLaunch);
+        }
+    }
+
+    for _ in ev_lost.iter() {
+        next_state.set(GameState::ReadyToLaunch);
+    }
+
+    // game over
+    for _ in ev_earth_destroyed.iter() {
+        next_state.set(GameState::Menu);
+    }
+}
+
+// the simulation alone on `level`, one fixed step per update, for tests of anything built on it
+#[cfg(test)]
+pub(crate) fn headless_app(level: LevelData) -> App {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GameplayPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )))
+        .insert_resource(CurrentLevel(level));
+    app.update();
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::system::SystemState, math::vec2, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::level::DebrisMotion;
+
+    fn with_debris(debris: Vec<Vec2>) -> LevelData {
+        LevelData {
+            par: 1,
+            sun: vec2(0.0, 15.0),
+            debris,
+            ..default()
+        }
+    }
+
+    fn set_state(app: &mut App, state: GameState) {
+        app.world.resource_mut::<NextState<GameState>>().set(state);
+        app.update();
+    }
+
+    fn state(app: &App) -> GameState {
+        app.world.resource::<State<GameState>>().get().clone()
+    }
+
+    fn aim(app: &mut App, direction: Vec2) {
+        app.insert_resource(Aim::from_direction(direction));
+    }
+
+    fn fire(app: &mut App, direction: Vec2, charge_frames: usize) {
+        set_state(app, GameState::Menu);
+        set_state(app, GameState::ReadyToLaunch);
+        aim(app, direction);
+        app.update();
+
+        set_state(app, GameState::ChargingLaunch);
+        for _ in 0..charge_frames {
+            app.update();
+        }
+        set_state(app, GameState::Launched);
+    }
+
+    fn run_until_landed(app: &mut App) {
+        for _ in 0..60 * 30 {
+            if state(app) != GameState::Launched {
+                return;
+            }
+            app.update();
+        }
+        panic!("crate never landed");
+    }
+
+    #[test]
+    fn crate_fired_at_sun_clears_empty_level() {
+        let mut app = headless_app(with_debris(vec![]));
+        fire(&mut app, Vec2::Y, 60);
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::Menu);
+        assert_eq!(app.world.resource::<Level>().0, 2);
+        assert_eq!(app.world.resource::<Score>().0, 1);
+    }
+
+    #[test]
+    fn crashes_are_announced_by_kind() {
+        let mut app = headless_app(with_debris(vec![]));
+        let mut incinerated = app
+            .world
+            .resource::<Events<CrateIncinerated>>()
+            .get_reader();
+        let mut lost = app
+            .world
+            .resource::<Events<CrateLostOnPlanet>>()
+            .get_reader();
+        let mut cleared = app.world.resource::<Events<LevelCleared>>().get_reader();
+        fire(&mut app, Vec2::Y, 60);
+        run_until_landed(&mut app);
+
+        let events = app.world.resource::<Events<CrateIncinerated>>();
+        assert_eq!(incinerated.iter(events).count(), 1);
+        let events = app.world.resource::<Events<CrateLostOnPlanet>>();
+        assert_eq!(lost.iter(events).count(), 0);
+        let events = app.world.resource::<Events<LevelCleared>>();
+        assert_eq!(
+            cleared.iter(events).next().map(|cleared| cleared.level),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn debris_in_flight_path_is_picked_up() {
+        let mut app = headless_app(with_debris(vec![vec2(0.0, -5.0)]));
+        fire(&mut app, Vec2::Y, 60);
+        run_until_landed(&mut app);
+
+        let floating = app
+            .world
+            .query_filtered::<Entity, (With<Debris>, Without<PickedUp>)>()
+            .iter(&app.world)
+            .count();
+        assert_eq!(floating, 0);
+        assert_eq!(app.world.resource::<Level>().0, 2);
+    }
+
+    // launch at a fixed tick with a fixed power, recording where the crate is and how it's turned each frame
+    fn flight_path(frames_per_tick: u32) -> (Vec<(u32, Vec2, Quat)>, GameState, usize) {
+        let mut app = headless_app(with_debris(vec![vec2(0.5, -6.0), vec2(20.0, 20.0)]));
+        let period = app.world.resource::<FixedTime>().period;
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(period * frames_per_tick));
+
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+        aim(&mut app, vec2(-0.1, 1.0));
+        while app.world.resource::<SimulationTick>().0 < 30 {
+            app.update();
+        }
+
+        app.world
+            .resource_mut::<LaunchPower>()
+            .0
+            .set_elapsed(Duration::from_secs_f32(1.2));
+        set_state(&mut app, GameState::Launched);
+
+        let mut path = Vec::new();
+        let mut q_crate = app
+            .world
+            .query_filtered::<(&Position, &Rotation), With<Crate>>();
+        while state(&app) == GameState::Launched {
+            let tick = app.world.resource::<SimulationTick>().0;
+            if let Ok((position, rotation)) = q_crate.get_single(&app.world) {
+                path.push((tick, position.current, rotation.current));
+            }
+            app.update();
+            assert!(tick < 60 * 30, "crate never landed");
+        }
+
+        (path, state(&app), app.world.resource::<Level>().0)
+    }
+
+    #[test]
+    fn flight_is_independent_of_frame_rate() {
+        let (fast, fast_state, fast_level) = flight_path(1);
+        let (slow, slow_state, slow_level) = flight_path(3);
+
+        assert!(slow.len() > 5);
+        for step in slow.iter() {
+            let same_tick = fast.iter().find(|(t, _, _)| *t == step.0);
+            assert_eq!(same_tick, Some(step));
+        }
+        assert_eq!(fast_state, slow_state);
+        assert_eq!(fast_level, slow_level);
+
+        // the crate tumbles at the same rate however often it's drawn
+        let (_, _, first) = fast[0];
+        let (_, _, second) = fast[1];
+        assert!((first.angle_between(second) - CRATE_SPIN / 60.0).abs() < 1e-4);
+    }
+
+    fn attractors(app: &mut App) -> Vec<Attractor> {
+        let mut attractors = SystemState::<Attractors>::new(&mut app.world);
+        attractors.get(&app.world).all()
+    }
+
+    // where a crate launched now with a second of charge would fly
+    fn predict(app: &mut App, attractors: &[Attractor]) -> Vec<Vec2> {
+        let earth = *app
+            .world
+            .query_filtered::<&Transform, With<Earth>>()
+            .single(&app.world);
+        let dt = app.world.resource::<FixedTime>().period.as_secs_f32();
+        let aim = app.world.resource::<Aim>();
+        predict_flight(
+            launch_position(&earth, aim),
+            launch_velocity(aim, 1.0),
+            0.5,
+            attractors,
+            app.world.resource::<SimulationTick>().0,
+            dt,
+            60 * 10,
+        )
+    }
+
+    // launch it, checking every step of the flight against the prediction
+    fn fly_as_predicted(app: &mut App, predicted: &[Vec2]) {
+        app.world
+            .resource_mut::<LaunchPower>()
+            .0
+            .set_elapsed(Duration::from_secs_f32(1.0));
+        set_state(app, GameState::Launched);
+
+        let mut q_crate = app.world.query_filtered::<&Position, With<Crate>>();
+        let mut steps = 0;
+        while let Ok(position) = q_crate.get_single(&app.world) {
+            assert_eq!(position.current, predicted[steps]);
+            steps += 1;
+            app.update();
+        }
+
+        // the crate is despawned in the same step the prediction ends
+        assert_eq!(steps, predicted.len() - 1);
+    }
+
+    #[test]
+    fn predicted_flight_matches_simulation() {
+        let mut app = headless_app(with_debris(vec![]));
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+        aim(&mut app, vec2(0.4, 1.0));
+        app.update();
+
+        let attractors = attractors(&mut app);
+        let predicted = predict(&mut app, &attractors);
+        assert!(predicted.len() < 60 * 10);
+        fly_as_predicted(&mut app, &predicted);
+    }
+
+    #[test]
+    fn predicted_flight_follows_orbiting_bodies() {
+        let mut app = headless_app(with_debris(vec![]));
+        let mut current_level = app.world.resource_mut::<CurrentLevel>();
+        current_level.0.sun = vec2(0.0, 40.0);
+        // swings across the flight path while the crate is on its way
+        current_level.0.bodies = vec![BodyData {
+            position: Vec2::ZERO,
+            radius: 1.0,
+            gravity: 20.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: Some(Orbit {
+                around: Around::Earth,
+                semi_major_axis: 14.0,
+                eccentricity: 0.3,
+                period: 2.0,
+                phase: 0.0,
+            }),
+            model: None,
+        }];
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+        aim(&mut app, vec2(0.4, 1.0));
+        for _ in 0..20 {
+            app.update();
+        }
+
+        let attractors = attractors(&mut app);
+        let predicted = predict(&mut app, &attractors);
+        assert!(predicted.len() < 60 * 10);
+
+        // held where it is now, the body sends the crate somewhere else
+        let held = attractors
+            .iter()
+            .map(|attractor| Attractor {
+                orbit: None,
+                ..attractor.clone()
+            })
+            .collect::<Vec<_>>();
+        assert_ne!(predict(&mut app, &held), predicted);
+
+        fly_as_predicted(&mut app, &predicted);
+    }
+
+    #[test]
+    fn level_bodies_smash_crates() {
+        let mut app = headless_app(with_debris(vec![vec2(0.0, -10.0)]));
+        app.world.resource_mut::<CurrentLevel>().0.bodies = vec![BodyData {
+            position: vec2(0.0, 0.0),
+            radius: 2.0,
+            gravity: 0.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: None,
+            model: None,
+        }];
+        let mut lost = app
+            .world
+            .resource::<Events<CrateLostOnPlanet>>()
+            .get_reader();
+        fire(&mut app, Vec2::Y, 60);
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::ReadyToLaunch);
+        assert_eq!(app.world.resource::<Level>().0, 1);
+        let events = app.world.resource::<Events<CrateLostOnPlanet>>();
+        assert_eq!(
+            lost.iter(events)
+                .map(|crash| crash.body)
+                .collect::<Vec<_>>(),
+            vec![BodyKind::Planet]
+        );
+        // the debris it picked up on the way is back where it was
+        let floating = app
+            .world
+            .query_filtered::<Entity, (With<Debris>, Without<PickedUp>)>()
+            .iter(&app.world)
+            .count();
+        assert_eq!(floating, 1);
+    }
+
+    #[test]
+    fn level_bodies_bring_their_model() {
+        let mut app = headless_app(with_debris(vec![]));
+        let body = |model: Option<&str>| BodyData {
+            position: vec2(30.0, 0.0),
+            radius: 1.0,
+            gravity: 0.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: None,
+            model: model.map(str::to_string),
+        };
+        app.world.resource_mut::<CurrentLevel>().0.bodies =
+            vec![body(Some("moon.glb#Scene0")), body(None)];
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+
+        let models = app
+            .world
+            .query_filtered::<Option<&BodyModel>, With<LevelBody>>()
+            .iter(&app.world)
+            .map(|model| model.map(|model| model.0.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(models.len(), 2);
+        assert!(models.contains(&Some("moon.glb#Scene0".to_string())));
+        assert!(models.contains(&None));
+    }
+
+    #[test]
+    fn moons_follow_their_planet() {
+        let mut app = headless_app(with_debris(vec![]));
+        let orbit = |around, semi_major_axis, period| Orbit {
+            around,
+            semi_major_axis,
+            eccentricity: 0.0,
+            period,
+            phase: 0.0,
+        };
+        let body = |orbit| BodyData {
+            position: Vec2::ZERO,
+            radius: 1.0,
+            gravity: 0.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: Some(orbit),
+            model: None,
+        };
+        app.world.resource_mut::<CurrentLevel>().0.bodies = vec![
+            body(orbit(Around::Sun, 30.0, 10.0)),
+            body(orbit(Around::Body(0), 4.0, -3.0)),
+        ];
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+
+        let mut q_bodies = app.world.query::<(&Position, &Orbiting)>();
+        for _ in 0..100 {
+            app.update();
+            let mut position_around = |around| {
+                q_bodies
+                    .iter(&app.world)
+                    .find(|(_, orbiting)| orbiting.orbit.around == around)
+                    .map(|(position, _)| position.current)
+                    .unwrap()
+            };
+            let planet = position_around(Around::Sun);
+            let moon = position_around(Around::Body(0));
+            assert!((planet.distance(vec2(0.0, 15.0)) - 30.0).abs() < 1e-3);
+            assert!((moon.distance(planet) - 4.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn moving_debris_stays_out_of_reach_of_the_sun() {
+        let mut app = headless_app(with_debris(vec![]));
+        // would graze the sun all the way round, collected by any crate burning up
+        app.world.resource_mut::<CurrentLevel>().0.moving_debris = vec![MovingDebris {
+            position: vec2(14.0, 15.0),
+            motion: DebrisMotion::Orbit { period: 4.0 },
+        }];
+        fire(&mut app, Vec2::Y, 60);
+
+        let mut q_debris = app
+            .world
+            .query_filtered::<&Position, (With<Debris>, Without<PickedUp>)>();
+        while state(&app) == GameState::Launched {
+            let debris = q_debris.single(&app.world).current;
+            assert!(debris.distance(vec2(0.0, 15.0)) >= SUN_RADIUS + PICKUP_RADIUS - 1e-4);
+            app.update();
+        }
+
+        // burnt up without clearing the level
+        assert_eq!(state(&app), GameState::ReadyToLaunch);
+        assert_eq!(q_debris.iter(&app.world).count(), 1);
+    }
+
+    #[test]
+    fn impacts_between_steps_are_found() {
+        // straight through a small target without ever being inside it at either end
+        let t = time_of_impact(
+            vec2(-10.0, 0.0),
+            vec2(10.0, 0.0),
+            Vec2::ZERO,
+            Vec2::ZERO,
+            1.0,
+        );
+        assert!((t.unwrap() - 0.45).abs() < 1e-5);
+
+        assert_eq!(
+            time_of_impact(
+                vec2(-10.0, 2.0),
+                vec2(10.0, 2.0),
+                Vec2::ZERO,
+                Vec2::ZERO,
+                1.0
+            ),
+            None
+        );
+        // stopping short, or already inside
+        assert_eq!(
+            time_of_impact(
+                vec2(-10.0, 0.0),
+                vec2(-5.0, 0.0),
+                Vec2::ZERO,
+                Vec2::ZERO,
+                1.0
+            ),
+            None
+        );
+        assert_eq!(
+            time_of_impact(Vec2::ZERO, vec2(5.0, 0.0), Vec2::ZERO, Vec2::ZERO, 1.0),
+            Some(0.0)
+        );
+        // a target crossing the path in the same step
+        let t = time_of_impact(
+            vec2(-10.0, 0.0),
+            vec2(10.0, 0.0),
+            vec2(0.0, -10.0),
+            vec2(0.0, 10.0),
+            1.0,
+        );
+        assert!(t.is_some());
+    }
+
+    // fire straight up at a charge that covers ~15 units every step
+    fn fire_fast(app: &mut App) {
+        set_state(app, GameState::Menu);
+        set_state(app, GameState::ReadyToLaunch);
+        aim(app, Vec2::Y);
+        app.update();
+        app.world
+            .resource_mut::<LaunchPower>()
+            .0
+            .set_elapsed(Duration::from_secs_f32(30.0));
+        set_state(app, GameState::Launched);
+    }
+
+    #[test]
+    fn fast_crates_hit_thin_bodies() {
+        let mut app = headless_app(with_debris(vec![]));
+        app.world.resource_mut::<CurrentLevel>().0.bodies = vec![BodyData {
+            position: vec2(0.0, -5.0),
+            radius: 0.5,
+            gravity: 0.0,
+            on_hit: OnHit::SmashCrate,
+            orbit: None,
+            model: None,
+        }];
+        let mut lost = app
+            .world
+            .resource::<Events<CrateLostOnPlanet>>()
+            .get_reader();
+        fire_fast(&mut app);
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::ReadyToLaunch);
+        let events = app.world.resource::<Events<CrateLostOnPlanet>>();
+        let hit = lost.iter(events).next().unwrap();
+        // right where it touched, not where the step ended
+        assert!(hit.position.distance(vec2(0.0, -5.5)) < 0.1);
+    }
+
+    #[test]
+    fn fast_crates_pick_up_debris_between_steps() {
+        let mut app = headless_app(with_debris(vec![vec2(0.0, -8.5)]));
+        fire_fast(&mut app);
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::Menu);
+        assert_eq!(app.world.resource::<Level>().0, 2);
+    }
+
+    #[test]
+    fn last_debris_picked_up_on_the_way_into_the_sun_clears_level() {
+        // just inside the sun, in reach a moment before the crate burns up
+        let mut app = headless_app(with_debris(vec![vec2(
+            0.0,
+            15.0 - SUN_RADIUS + PICKUP_RADIUS - 0.1,
+        )]));
+        fire_fast(&mut app);
+
+        let mut q_crate = app.world.query_filtered::<Entity, With<CurrentCrate>>();
+        let mut q_floating = app
+            .world
+            .query_filtered::<Entity, (With<Debris>, Without<PickedUp>)>();
+        while q_crate.iter(&app.world).next().is_some() {
+            assert_eq!(q_floating.iter(&app.world).count(), 1);
+            app.update();
+        }
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::Menu);
+        assert_eq!(app.world.resource::<Level>().0, 2);
+    }
+
+    #[test]
+    fn crate_falling_back_destroys_earth() {
+        let mut app = headless_app(with_debris(vec![]));
+        let mut destroyed = app.world.resource::<Events<EarthDestroyed>>().get_reader();
+        fire(&mut app, Vec2::NEG_Y, 0);
+        run_until_landed(&mut app);
+
+        assert_eq!(state(&app), GameState::Menu);
+        assert_eq!(app.world.resource::<Level>().0, 1);
+        let events = app.world.resource::<Events<EarthDestroyed>>();
+        assert_eq!(destroyed.iter(events).count(), 1);
+    }
+}
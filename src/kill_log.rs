@@ -0,0 +1,694 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    button::{button_style, overlay_panel, ButtonCommands},
+    gameplay::{
+        BodyKind, CrateIncinerated, CrateLaunched, CrateLostOnPlanet, DebrisCollected,
+        EarthDestroyed, GameState, Level, OnHit, Simulation,
+    },
+    locale::{Locale, Localized},
+    replay::is_replaying,
+    storage::{self, StorageDir},
+    PrimaryColorHue,
+};
+
+pub struct KillLogPlugin;
+
+impl Plugin for KillLogPlugin {
+    fn build(&self, app: &mut App) {
+        match load_kill_log() {
+            Some(kill_log) => app.insert_resource(kill_log),
+            None => app
+                .insert_resource(KillLog::default())
+                .insert_resource(KillLogReadOnly),
+        };
+
+        app.add_systems(FixedUpdate, log_crashes.after(Simulation))
+            .add_systems(
+                Update,
+                write_kill_log.run_if(
+                    resource_changed::<KillLog>()
+                        .and_then(not(is_replaying))
+                        .and_then(not(resource_exists::<KillLogReadOnly>())),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Menu), spawn_history_button)
+            .add_systems(OnExit(GameState::Menu), despawn_history_ui)
+            .add_systems(
+                Update,
+                (
+                    interact_history_button,
+                    interact_history_panel,
+                    scroll_history,
+                )
+                    .run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+const KILL_LOG_KEY: &str = "incinerations.ron";
+
+// every crate that didn't make it back, oldest first
+#[derive(Resource, Default)]
+pub struct KillLog(pub Vec<Incineration>);
+
+// the log on disk couldn't be read, leave it for a fixed build instead of writing over it
+#[derive(Resource)]
+struct KillLogReadOnly;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Incineration {
+    pub item: String,
+    pub level: usize,
+    // seconds since the unix epoch
+    pub timestamp: u64,
+    pub outcome: OnHit,
+    // what it hit, unknown for crashes logged before bodies were told apart
+    #[serde(default)]
+    pub body: Option<BodyKind>,
+    // debris the crate had picked up on the way
+    pub debris: usize,
+}
+
+impl Incineration {
+    fn describe(&self, locale: &Locale) -> String {
+        let outcome = locale.text(match self.outcome {
+            OnHit::Incinerate => "history.into_sun",
+            OnHit::DestroyEarth => "history.took_earth",
+            OnHit::SmashCrate => "history.smashed",
+        });
+        let outcome = match self.body {
+            Some(body) => locale.format(
+                "history.outcome_on",
+                &[("outcome", &outcome), ("body", &locale.text(body.key()))],
+            ),
+            None => outcome,
+        };
+        locale.format(
+            "history.entry",
+            &[
+                ("time", &format_timestamp(self.timestamp)),
+                ("level", &self.level),
+                ("item", &locale.cargo(&self.item)),
+                ("debris", &self.debris),
+                ("outcome", &outcome),
+            ],
+        )
+    }
+}
+
+impl KillLog {
+    // how many of each item ended up in the sun, most first
+    pub fn totals(&self) -> Vec<(String, usize)> {
+        let mut totals = BTreeMap::<&str, usize>::new();
+        for entry in self.0.iter() {
+            if entry.outcome == OnHit::Incinerate {
+                *totals.entry(&entry.item).or_default() += 1;
+            }
+        }
+
+        let mut totals = totals
+            .into_iter()
+            .map(|(item, count)| (item.to_string(), count))
+            .collect::<Vec<_>>();
+        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = "item,level,time,outcome,body,debris\n".to_string();
+        for entry in self.0.iter() {
+            let body = entry.body.map(|body| format!("{:?}", body));
+            csv += &format!(
+                "{},{},{},{:?},{},{}\n",
+                csv_field(&entry.item),
+                entry.level,
+                format_timestamp(entry.timestamp),
+                entry.outcome,
+                body.unwrap_or_default(),
+                entry.debris
+            );
+        }
+        csv
+    }
+}
+
+// quoted when it would otherwise split the row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_time() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+// "2026-10-18 14:03", in utc
+fn format_timestamp(timestamp: u64) -> String {
+    // days since 1970 to a civil date, from howard hinnant's date algorithms
+    let days = (timestamp / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let seconds = timestamp % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+// none if there is a log but it couldn't be read
+fn load_kill_log() -> Option<KillLog> {
+    storage::load(StorageDir::Data, KILL_LOG_KEY).map_or_else(
+        || Some(KillLog::default()),
+        |contents| parse_kill_log(&contents),
+    )
+}
+
+fn parse_kill_log(contents: &str) -> Option<KillLog> {
+    match ron::from_str(contents) {
+        Ok(entries) => Some(KillLog(entries)),
+        Err(e) => {
+            warn!(
+                "ignoring unreadable incineration log, crashes will not be saved: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+fn write_kill_log(kill_log: Res<KillLog>) {
+    let contents = match ron::to_string(&kill_log.0) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("failed to serialize incineration log: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = storage::save(StorageDir::Data, KILL_LOG_KEY, &contents) {
+        error!("failed to save incineration log: {}", e);
+    }
+}
+
+// the crate in the air, for what gets logged when it comes down
+#[derive(Default)]
+struct InFlight {
+    level: usize,
+    debris: usize,
+}
+
+// read in the same fixed step the events are sent, like the replay recorder
+fn log_crashes(
+    mut in_flight: Local<InFlight>,
+    mut ev_launched: EventReader<CrateLaunched>,
+    mut ev_picked_up: EventReader<DebrisCollected>,
+    mut ev_incinerated: EventReader<CrateIncinerated>,
+    mut ev_earth_destroyed: EventReader<EarthDestroyed>,
+    mut ev_lost: EventReader<CrateLostOnPlanet>,
+    level: Res<Level>,
+    mut kill_log: ResMut<KillLog>,
+) {
+    // clearing the level moves it on in the same step, so remember it from the launch
+    for _ in ev_launched.iter() {
+        *in_flight = InFlight {
+            level: level.0,
+            debris: 0,
+        };
+    }
+    in_flight.debris += ev_picked_up.iter().count();
+
+    let crashes = ev_incinerated
+        .iter()
+        .map(|crash| (&crash.name, crash.body, OnHit::Incinerate))
+        .chain(
+            ev_earth_destroyed
+                .iter()
+                .map(|crash| (&crash.name, crash.body, OnHit::DestroyEarth)),
+        )
+        .chain(
+            ev_lost
+                .iter()
+                .map(|crash| (&crash.name, crash.body, OnHit::SmashCrate)),
+        );
+    for (item, body, outcome) in crashes {
+        kill_log.0.push(Incineration {
+            item: item.clone(),
+            level: in_flight.level,
+            timestamp: unix_time(),
+            outcome,
+            body: Some(body),
+            debris: in_flight.debris,
+        });
+    }
+}
+
+#[derive(Component)]
+struct HistoryButton;
+
+#[derive(Component)]
+struct HistoryPanel;
+
+#[derive(Component)]
+enum HistoryPanelButton {
+    ExportCsv,
+    ExportJson,
+    Close,
+}
+
+#[derive(Component)]
+struct ExportStatusText;
+
+// the column of history lines, moved up and down inside a clipped parent
+#[derive(Component, Default)]
+struct ScrollingList {
+    position: f32,
+}
+
+fn spawn_history_button(mut commands: Commands, kill_log: Res<KillLog>, locale: Res<Locale>) {
+    if kill_log.0.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(160.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            HistoryButton,
+            Localized::new("history.title"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("history.title"),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_history_ui(
+    mut commands: Commands,
+    q_ui: Query<Entity, Or<(With<HistoryButton>, With<HistoryPanel>)>>,
+) {
+    for ent in q_ui.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_history_button(
+    mut commands: Commands,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<HistoryButton>)>,
+    q_panel: Query<(), With<HistoryPanel>>,
+    kill_log: Res<KillLog>,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed && q_panel.is_empty() {
+            spawn_history_panel(&mut commands, &kill_log, primary_color_hue.0, &locale);
+        }
+    }
+}
+
+fn spawn_history_panel(commands: &mut Commands, kill_log: &KillLog, hue: f32, locale: &Locale) {
+    let text_style = TextStyle {
+        font_size: 22.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    let heading_style = TextStyle {
+        font_size: 28.0,
+        color: Color::WHITE.with_a(0.6),
+        ..default()
+    };
+
+    let totals = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                width: Val::Px(280.0),
+                margin: UiRect::right(Val::Px(30.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("history.incinerated"),
+                heading_style.clone(),
+            ));
+            for (item, count) in kill_log.totals() {
+                parent.spawn(TextBundle::from_section(
+                    format!("{} x{}", locale.cargo(&item), count),
+                    text_style.clone(),
+                ));
+            }
+        })
+        .id();
+
+    // newest first
+    let list = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            ScrollingList::default(),
+        ))
+        .with_children(|parent| {
+            for entry in kill_log.0.iter().rev() {
+                parent.spawn(TextBundle::from_section(
+                    entry.describe(locale),
+                    text_style.clone(),
+                ));
+            }
+        })
+        .id();
+    let history = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                width: Val::Px(720.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("history.history"),
+                heading_style.clone(),
+            ));
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        height: Val::Vh(45.0),
+                        overflow: Overflow::clip_y(),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .add_child(list);
+        })
+        .id();
+
+    let columns = commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::vertical(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .push_children(&[totals, history])
+        .id();
+
+    let status = commands
+        .spawn((
+            ExportStatusText,
+            TextBundle::from_section("", heading_style.clone()),
+        ))
+        .id();
+
+    let mut items = Vec::new();
+    // there is nowhere to put a file on the web
+    if cfg!(not(target_arch = "wasm32")) {
+        items.push(("history.export_csv", HistoryPanelButton::ExportCsv));
+        items.push(("history.export_json", HistoryPanelButton::ExportJson));
+    }
+    items.push(("common.close", HistoryPanelButton::Close));
+    let buttons = items
+        .into_iter()
+        .map(|(key, button)| {
+            commands
+                .spawn_text_button(&locale.text(key), hue)
+                .insert((
+                    button,
+                    Style {
+                        width: Val::Px(260.0),
+                        height: Val::Px(60.0),
+                        margin: UiRect::all(Val::Px(8.0)),
+                        ..button_style()
+                    },
+                ))
+                .id()
+        })
+        .collect::<Vec<_>>();
+    let button_row = commands
+        .spawn(NodeBundle::default())
+        .push_children(&buttons)
+        .id();
+
+    commands
+        .spawn((HistoryPanel, overlay_panel(10)))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("history.title"),
+                TextStyle {
+                    font_size: 64.0,
+                    ..text_style.clone()
+                },
+            ));
+        })
+        .push_children(&[columns, status, button_row]);
+}
+
+// what to tell the player about it
+fn export(key: &str, contents: Result<String, String>, locale: &Locale) -> String {
+    let saved = contents.and_then(|contents| storage::save(StorageDir::Data, key, &contents));
+    match saved {
+        Ok(()) => {
+            let path = storage::location(StorageDir::Data, key);
+            locale.format("history.saved", &[("path", &path)])
+        }
+        Err(e) => {
+            error!("failed to export incineration log to {}: {}", key, e);
+            locale.format("history.export_failed", &[("error", &e)])
+        }
+    }
+}
+
+fn interact_history_panel(
+    mut commands: Commands,
+    q_button: Query<(&Interaction, &HistoryPanelButton), Changed<Interaction>>,
+    q_panel: Query<Entity, With<HistoryPanel>>,
+    mut q_status: Query<&mut Text, With<ExportStatusText>>,
+    kill_log: Res<KillLog>,
+    locale: Res<Locale>,
+) {
+    for (interaction, button) in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let status = match button {
+            HistoryPanelButton::ExportCsv => {
+                export("exports/incinerations.csv", Ok(kill_log.to_csv()), &locale)
+            }
+            HistoryPanelButton::ExportJson => export(
+                "exports/incinerations.json",
+                serde_json::to_string_pretty(&kill_log.0).map_err(|e| e.to_string()),
+                &locale,
+            ),
+            HistoryPanelButton::Close => {
+                for ent in q_panel.iter() {
+                    commands.entity(ent).despawn_recursive();
+                }
+                continue;
+            }
+        };
+        for mut text in q_status.iter_mut() {
+            text.sections[0].value = status.clone();
+        }
+    }
+}
+
+// mouse wheel, or dragging a finger over the list
+fn scroll_history(
+    mut ev_wheel: EventReader<MouseWheel>,
+    touches: Res<Touches>,
+    mut q_list: Query<(&mut ScrollingList, &mut Style, &Parent, &Node)>,
+    q_node: Query<&Node>,
+) {
+    let mut dy = ev_wheel
+        .iter()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y * 24.0,
+            MouseScrollUnit::Pixel => wheel.y,
+        })
+        .sum::<f32>();
+    dy += touches.iter().map(|touch| touch.delta().y).sum::<f32>();
+    if dy == 0.0 {
+        return;
+    }
+
+    for (mut list, mut style, parent, list_node) in q_list.iter_mut() {
+        let Ok(container) = q_node.get(parent.get()) else {
+            continue;
+        };
+        let max_scroll = (list_node.size().y - container.size().y).max(0.0);
+        list.position = (list.position + dy).clamp(-max_scroll, 0.0);
+        style.top = Val::Px(list.position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(item: &str, outcome: OnHit) -> Incineration {
+        Incineration {
+            item: item.to_string(),
+            level: 1,
+            timestamp: 1_700_000_000,
+            outcome,
+            body: Some(BodyKind::Sun),
+            debris: 0,
+        }
+    }
+
+    #[test]
+    fn timestamps_are_readable() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+        // leap day
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+    }
+
+    #[test]
+    fn totals_only_count_what_reached_the_sun() {
+        let kill_log = KillLog(vec![
+            entry("Nuclear waste", OnHit::Incinerate),
+            entry("Rubber duck", OnHit::Incinerate),
+            entry("Nuclear waste", OnHit::Incinerate),
+            entry("Nuclear waste", OnHit::SmashCrate),
+            entry("Rubber duck", OnHit::DestroyEarth),
+        ]);
+        assert_eq!(
+            kill_log.totals(),
+            vec![
+                ("Nuclear waste".to_string(), 2),
+                ("Rubber duck".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_quotes_awkward_names() {
+        let kill_log = KillLog(vec![entry("Socks, \"clean\"", OnHit::Incinerate)]);
+        assert_eq!(
+            kill_log.to_csv(),
+            "item,level,time,outcome,body,debris\n\
+             \"Socks, \"\"clean\"\"\",1,2023-11-14 22:13,Incinerate,Sun,0\n"
+        );
+    }
+
+    #[test]
+    fn older_logs_load_without_bodies() {
+        let entries = ron::from_str::<Vec<Incineration>>(
+            "[(item: \"Rubber duck\", level: 2, timestamp: 0, outcome: SmashCrate, debris: 1)]",
+        )
+        .unwrap();
+        assert_eq!(entries[0].body, None);
+        assert_eq!(
+            KillLog(entries).to_csv(),
+            "item,level,time,outcome,body,debris\n\
+             Rubber duck,2,1970-01-01 00:00,SmashCrate,,1\n"
+        );
+    }
+
+    #[test]
+    fn unreadable_logs_are_not_loaded() {
+        assert!(parse_kill_log("[(item: \"Rubber duck\", level: \"two\")]").is_none());
+        assert_eq!(parse_kill_log("[]").unwrap().0, vec![]);
+    }
+
+    #[test]
+    fn crashes_are_logged_with_their_launch() {
+        let mut app = App::new();
+        app.add_event::<CrateLaunched>()
+            .add_event::<DebrisCollected>()
+            .add_event::<CrateIncinerated>()
+            .add_event::<EarthDestroyed>()
+            .add_event::<CrateLostOnPlanet>()
+            .insert_resource(Level(3))
+            .insert_resource(KillLog::default())
+            .add_systems(Update, log_crashes);
+
+        app.world.send_event(CrateLaunched {
+            power: 1.0,
+            aim: 0.0,
+            tick: 0,
+        });
+        app.update();
+
+        // clearing the level moves it on before the log hears about the crash
+        app.world.resource_mut::<Level>().0 = 4;
+        app.world.send_event(DebrisCollected);
+        app.world.send_event(DebrisCollected);
+        app.world.send_event(CrateIncinerated {
+            name: "Nuclear waste".to_string(),
+            body: BodyKind::Sun,
+            position: Vec2::ZERO,
+        });
+        app.update();
+
+        let kill_log = app.world.resource::<KillLog>();
+        assert_eq!(kill_log.0.len(), 1);
+        let logged = &kill_log.0[0];
+        assert_eq!(logged.item, "Nuclear waste");
+        assert_eq!(logged.level, 3);
+        assert_eq!(logged.outcome, OnHit::Incinerate);
+        assert_eq!(logged.body, Some(BodyKind::Sun));
+        assert_eq!(logged.debris, 2);
+    }
+}
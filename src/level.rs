@@ -0,0 +1,485 @@
+use std::{f32::consts::TAU, path::PathBuf};
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadState, LoadedAsset},
+    ecs::system::SystemParam,
+    math::vec2,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    endless::{self, EndlessSeed},
+    gameplay::{CurrentLevel, GameState, Level, OnHit},
+    utils::AssetHandle,
+};
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<LevelData>()
+            .add_asset::<LevelList>()
+            .init_asset_loader::<LevelDataLoader>()
+            .init_asset_loader::<LevelListLoader>()
+            .add_systems(Startup, load_levels)
+            .add_systems(OnEnter(GameState::Menu), update_current_level)
+            .add_systems(
+                Update,
+                update_current_level
+                    .run_if(in_state(GameState::Menu).and_then(resource_changed::<Level>())),
+            )
+            .add_systems(Update, wait_for_levels.run_if(in_state(GameState::Loading)));
+    }
+}
+
+// layout of a single level, loaded from assets/levels/*.level.ron
+#[derive(Deserialize, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "3b7c61a2-4f0e-4d8a-9a57-2f5c1e0b9d41"]
+pub struct LevelData {
+    pub par: usize,
+    pub sun: Vec2,
+    // the early levels have nothing in the way
+    #[serde(default)]
+    pub mercury: Option<Orbit>,
+    pub debris: Vec<Vec2>,
+    // pieces that move, each with its own motion
+    #[serde(default)]
+    pub moving_debris: Vec<MovingDebris>,
+    #[serde(default)]
+    pub asteroids: Vec<AsteroidData>,
+    #[serde(default)]
+    pub bodies: Vec<BodyData>,
+}
+
+impl LevelData {
+    // anything that would put bodies at NaN, turned away by the loader instead
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(orbit) = &self.mercury {
+            orbit.validate(0).map_err(|e| format!("mercury: {}", e))?;
+        }
+        for (index, body) in self.bodies.iter().enumerate() {
+            if let Some(orbit) = &body.orbit {
+                orbit
+                    .validate(index)
+                    .map_err(|e| format!("body {}: {}", index, e))?;
+            }
+        }
+        for (index, debris) in self.moving_debris.iter().enumerate() {
+            debris
+                .motion
+                .validate()
+                .map_err(|e| format!("moving debris {}: {}", index, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MovingDebris {
+    pub position: Vec2,
+    pub motion: DebrisMotion,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DebrisMotion {
+    // circles the sun at the distance it starts at, a negative period goes clockwise
+    Orbit { period: f32 },
+    // swings out to `position + reach`, back through `position` to `position - reach`
+    Oscillate { reach: Vec2, period: f32 },
+    // floats off in a straight line, in units per second
+    Drift { velocity: Vec2 },
+}
+
+impl DebrisMotion {
+    fn validate(&self) -> Result<(), String> {
+        match *self {
+            DebrisMotion::Orbit { period } | DebrisMotion::Oscillate { period, .. }
+                if period == 0.0 =>
+            {
+                Err("period can't be 0".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // where debris starting at `start` is after `elapsed_seconds`
+    pub fn position_at(&self, start: Vec2, sun: Vec2, elapsed_seconds: f32) -> Vec2 {
+        match *self {
+            DebrisMotion::Orbit { period } => {
+                let angle = TAU * elapsed_seconds / period;
+                sun + Vec2::from_angle(angle).rotate(start - sun)
+            }
+            DebrisMotion::Oscillate { reach, period } => {
+                start + reach * (TAU * elapsed_seconds / period).sin()
+            }
+            DebrisMotion::Drift { velocity } => start + velocity * elapsed_seconds,
+        }
+    }
+}
+
+// a rock crates smash on, nothing in the campaign has these yet
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AsteroidData {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+// any other planet, moon or hazard, either sitting still at `position` or orbiting
+#[derive(Deserialize, Debug, Clone)]
+pub struct BodyData {
+    #[serde(default)]
+    pub position: Vec2,
+    pub radius: f32,
+    #[serde(default)]
+    pub gravity: f32,
+    pub on_hit: OnHit,
+    #[serde(default)]
+    pub orbit: Option<Orbit>,
+    // the scene it's drawn with, like "mercury.glb#Scene0". mercury's when it's left out
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+// what an orbit goes around
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Around {
+    #[default]
+    Sun,
+    Earth,
+    // an earlier entry in the level's bodies
+    Body(usize),
+}
+
+// an ellipse with its parent at one focus and the closest point straight to the right.
+// a negative period goes clockwise, `phase` is how far along it starts, in radians
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Orbit {
+    #[serde(default)]
+    pub around: Around,
+    pub semi_major_axis: f32,
+    #[serde(default)]
+    pub eccentricity: f32,
+    pub period: f32,
+    #[serde(default)]
+    pub phase: f32,
+}
+
+impl Orbit {
+    // for a body with `index` others spawned before it
+    fn validate(&self, index: usize) -> Result<(), String> {
+        if self.period == 0.0 {
+            return Err("orbit period can't be 0".to_string());
+        }
+        if !(0.0..1.0).contains(&self.eccentricity) {
+            return Err(format!(
+                "orbit eccentricity must be at least 0 and below 1, not {}",
+                self.eccentricity
+            ));
+        }
+        match self.around {
+            Around::Body(parent) if parent >= index => Err(format!(
+                "can only orbit an earlier body, not body {}",
+                parent
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // where the body is relative to its parent, straight from kepler's equation
+    pub fn offset_at(&self, elapsed_seconds: f32) -> Vec2 {
+        let e = self.eccentricity;
+        let mean_anomaly = TAU * elapsed_seconds / self.period + self.phase;
+
+        // newton's method, plenty for anything short of a parabola
+        let mut eccentric_anomaly = mean_anomaly;
+        for _ in 0..8 {
+            eccentric_anomaly -= (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+                / (1.0 - e * eccentric_anomaly.cos());
+        }
+
+        vec2(
+            self.semi_major_axis * (eccentric_anomaly.cos() - e),
+            self.semi_major_axis * (1.0 - e * e).sqrt() * eccentric_anomaly.sin(),
+        )
+    }
+}
+
+// nothing to play until the level list has loaded
+impl Default for LevelData {
+    fn default() -> Self {
+        LevelData {
+            par: 0,
+            sun: vec2(0.0, 15.0),
+            mercury: None,
+            debris: Vec::new(),
+            moving_debris: Vec::new(),
+            asteroids: Vec::new(),
+            bodies: Vec::new(),
+        }
+    }
+}
+
+// ordered list of campaign levels, loaded from assets/levels/*.levels.ron
+#[derive(TypeUuid, TypePath, Debug)]
+#[uuid = "9e1d4b07-6a3c-4f52-8e2b-0d7a5c3f1e68"]
+pub struct LevelList(pub Vec<Handle<LevelData>>);
+
+#[derive(Deserialize)]
+struct LevelListFile {
+    levels: Vec<String>,
+}
+
+#[derive(Default)]
+struct LevelDataLoader;
+
+impl AssetLoader for LevelDataLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let level = ron::de::from_bytes::<LevelData>(bytes)?;
+            level.validate().map_err(bevy::asset::Error::msg)?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default)]
+struct LevelListLoader;
+
+impl AssetLoader for LevelListLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let file = ron::de::from_bytes::<LevelListFile>(bytes)?;
+
+            let paths: Vec<AssetPath<'static>> = file
+                .levels
+                .into_iter()
+                .map(|path| AssetPath::new(PathBuf::from(path), None))
+                .collect();
+            let handles = paths
+                .iter()
+                .map(|path| load_context.get_handle(path.clone()))
+                .collect();
+
+            load_context
+                .set_default_asset(LoadedAsset::new(LevelList(handles)).with_dependencies(paths));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
+
+fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AssetHandle::<Level, LevelList>::new(
+        asset_server.load("levels/campaign.levels.ron"),
+    ));
+}
+
+// stay in Loading until the level list and every level in it is ready
+fn wait_for_levels(
+    asset_server: Res<AssetServer>,
+    level_list: Res<AssetHandle<Level, LevelList>>,
+    level_lists: Res<Assets<LevelList>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let load_state = match level_lists.get(&level_list.handle) {
+        Some(list) => asset_server.get_group_load_state(list.0.iter().map(|handle| handle.id())),
+        None => asset_server.get_load_state(&level_list.handle),
+    };
+
+    match load_state {
+        LoadState::Loaded => next_state.set(GameState::Menu),
+        LoadState::Failed => {
+            error!("failed to load levels, only endless mode will be available");
+            next_state.set(GameState::Menu);
+        }
+        _ => {}
+    }
+}
+
+// pick the layout that will be spawned when leaving the menu
+fn update_current_level(mut commands: Commands, level: Res<Level>, levels: Levels) {
+    commands.insert_resource(CurrentLevel(levels.level_data(&level)));
+}
+
+// access to the loaded campaign, indexed by the Level resource
+#[derive(SystemParam)]
+pub struct Levels<'w> {
+    level_list: Res<'w, AssetHandle<Level, LevelList>>,
+    level_lists: Res<'w, Assets<LevelList>>,
+    levels: Res<'w, Assets<LevelData>>,
+    endless_seed: Res<'w, EndlessSeed>,
+}
+
+impl<'w> Levels<'w> {
+    // number of levels before endless mode starts
+    pub fn campaign_len(&self) -> usize {
+        self.level_lists
+            .get(&self.level_list.handle)
+            .map(|list| list.0.len())
+            .unwrap_or(0)
+    }
+
+    pub fn get(&self, level: &Level) -> Option<&LevelData> {
+        let list = self.level_lists.get(&self.level_list.handle)?;
+        let handle = list.0.get(level.0.checked_sub(1)?)?;
+        self.levels.get(handle)
+    }
+
+    pub fn is_endless(&self, level: &Level) -> bool {
+        self.level_lists.contains(&self.level_list.handle) && level.0 > self.campaign_len()
+    }
+
+    pub fn level_data(&self, level: &Level) -> LevelData {
+        self.level_data_seeded(level, self.endless_seed.0)
+    }
+
+    // same as level_data, but endless levels come from `seed` instead of the current run
+    pub fn level_data_seeded(&self, level: &Level, seed: u64) -> LevelData {
+        self.get(level).cloned().unwrap_or_else(|| {
+            let depth = level.0.saturating_sub(self.campaign_len()).max(1);
+            endless::generate(depth, seed)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(eccentricity: f32, period: f32) -> Orbit {
+        Orbit {
+            around: Around::Sun,
+            semi_major_axis: 10.0,
+            eccentricity,
+            period,
+            phase: 0.0,
+        }
+    }
+
+    #[test]
+    fn orbits_stay_on_their_ellipse() {
+        // closest at 5 to the right, furthest at 15 to the left half a period later
+        let orbit = orbit(0.5, 8.0);
+        assert!(orbit.offset_at(0.0).abs_diff_eq(vec2(5.0, 0.0), 1e-4));
+        assert!(orbit.offset_at(4.0).abs_diff_eq(vec2(-15.0, 0.0), 1e-4));
+        assert!(orbit.offset_at(8.0).abs_diff_eq(orbit.offset_at(0.0), 1e-4));
+
+        for step in 0..100 {
+            let distance = orbit.offset_at(step as f32 * 0.1).length();
+            assert!((5.0 - 1e-4..=15.0 + 1e-4).contains(&distance));
+        }
+    }
+
+    #[test]
+    fn orbiting_debris_keeps_its_distance() {
+        let sun = vec2(0.0, 15.0);
+        let start = vec2(20.0, 15.0);
+        let motion = DebrisMotion::Orbit { period: 40.0 };
+
+        assert!(motion
+            .position_at(start, sun, 10.0)
+            .abs_diff_eq(vec2(0.0, 35.0), 1e-4));
+        for step in 0..100 {
+            let position = motion.position_at(start, sun, step as f32 * 0.7);
+            assert!((position.distance(sun) - 20.0).abs() < 1e-3);
+        }
+    }
+
+    fn level(bodies: Vec<Orbit>) -> LevelData {
+        LevelData {
+            bodies: bodies
+                .into_iter()
+                .map(|orbit| BodyData {
+                    position: Vec2::ZERO,
+                    radius: 1.0,
+                    gravity: 0.0,
+                    on_hit: OnHit::SmashCrate,
+                    orbit: Some(orbit),
+                    model: None,
+                })
+                .collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn orbits_that_go_nowhere_are_rejected() {
+        assert!(level(vec![orbit(0.5, 8.0)]).validate().is_ok());
+        assert!(level(vec![orbit(0.5, 0.0)]).validate().is_err());
+        assert!(level(vec![orbit(1.0, 8.0)]).validate().is_err());
+        assert!(level(vec![orbit(-0.1, 8.0)]).validate().is_err());
+
+        let moon = Orbit {
+            around: Around::Body(0),
+            ..orbit(0.0, 2.0)
+        };
+        assert!(level(vec![orbit(0.0, 8.0), moon]).validate().is_ok());
+        assert!(level(vec![moon, orbit(0.0, 8.0)]).validate().is_err());
+
+        let no_mercury = LevelData {
+            mercury: Some(orbit(0.0, 0.0)),
+            ..default()
+        };
+        assert!(no_mercury.validate().is_err());
+    }
+
+    #[test]
+    fn debris_that_goes_nowhere_is_rejected() {
+        let debris = |motion| LevelData {
+            moving_debris: vec![MovingDebris {
+                position: vec2(20.0, 15.0),
+                motion,
+            }],
+            ..default()
+        };
+        assert!(debris(DebrisMotion::Orbit { period: 40.0 })
+            .validate()
+            .is_ok());
+        assert!(debris(DebrisMotion::Orbit { period: 0.0 })
+            .validate()
+            .is_err());
+        let swing = |period| DebrisMotion::Oscillate {
+            reach: vec2(5.0, 0.0),
+            period,
+        };
+        assert!(debris(swing(3.0)).validate().is_ok());
+        assert!(debris(swing(0.0)).validate().is_err());
+    }
+
+    #[test]
+    fn campaign_levels_are_valid() {
+        let list: LevelListFile =
+            ron::from_str(&std::fs::read_to_string("assets/levels/campaign.levels.ron").unwrap())
+                .unwrap();
+        for path in list.levels {
+            let contents = std::fs::read_to_string(format!("assets/{}", path)).unwrap();
+            let level: LevelData = ron::from_str(&contents).unwrap();
+            assert_eq!(level.validate(), Ok(()), "{}", path);
+        }
+    }
+
+    #[test]
+    fn negative_period_goes_clockwise() {
+        assert!(orbit(0.0, 8.0).offset_at(1.0).y > 0.0);
+        assert!(orbit(0.0, -8.0).offset_at(1.0).y < 0.0);
+    }
+}
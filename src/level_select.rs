@@ -0,0 +1,358 @@
+use bevy::prelude::*;
+
+use crate::{
+    button::{
+        button_background_color, button_hover_color, button_style, overlay_panel, ButtonCommands,
+    },
+    controls::{Action, BindingKind, Bindings, PlayerInput},
+    gameplay::{CurrentLevel, GameState, Level},
+    level::Levels,
+    locale::{Locale, Localized},
+    save::SaveData,
+    PrimaryColorHue,
+};
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Menu), spawn_level_select_button)
+            .add_systems(OnExit(GameState::Menu), despawn_level_select_ui)
+            .add_systems(
+                Update,
+                (
+                    open_level_select,
+                    (navigate_level_select, choose_level, highlight_focused_entry)
+                        .chain()
+                        .run_if(resource_exists::<LevelSelectFocus>()),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+// entries per row, up and down move by this much
+const COLUMNS: usize = 3;
+const ENTRY_WIDTH: f32 = 200.0;
+const ENTRY_MARGIN: f32 = 10.0;
+
+#[derive(Component)]
+struct LevelSelectButton;
+
+#[derive(Component)]
+struct LevelSelectPanel;
+
+#[derive(Component)]
+struct LevelEntry {
+    index: usize,
+    // level to start, None for the back button
+    level: Option<usize>,
+    locked: bool,
+}
+
+// entry picked by keyboard or gamepad, exists while the panel is open
+#[derive(Resource)]
+struct LevelSelectFocus(usize);
+
+fn spawn_level_select_button(
+    mut commands: Commands,
+    save: Res<SaveData>,
+    bindings: Res<Bindings>,
+    locale: Res<Locale>,
+) {
+    // nothing to pick between yet
+    if save.unlocked_level <= 1 {
+        return;
+    }
+
+    let key = bindings.describe(Action::LevelSelect, BindingKind::Keyboard, &locale);
+    let label = Localized::new("level_select.open").with("binding", key);
+    let text = label.text(&locale);
+
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(100.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            LevelSelectButton,
+            label,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_level_select_ui(
+    mut commands: Commands,
+    q_ui: Query<Entity, Or<(With<LevelSelectButton>, With<LevelSelectPanel>)>>,
+) {
+    for ent in q_ui.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    commands.remove_resource::<LevelSelectFocus>();
+}
+
+fn open_level_select(
+    mut commands: Commands,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<LevelSelectButton>)>,
+    input: Res<PlayerInput>,
+    focus: Option<Res<LevelSelectFocus>>,
+    save: Res<SaveData>,
+    levels: Levels,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    if focus.is_some() || save.unlocked_level <= 1 {
+        return;
+    }
+
+    let clicked = q_button
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if clicked || input.just_pressed(Action::LevelSelect) {
+        spawn_level_select(&mut commands, &save, &levels, primary_color_hue.0, &locale);
+    }
+}
+
+fn spawn_level_select(
+    commands: &mut Commands,
+    save: &SaveData,
+    levels: &Levels,
+    hue: f32,
+    locale: &Locale,
+) {
+    let campaign_len = levels.campaign_len();
+
+    // (label, status, level, locked) for every campaign level, endless, and back
+    let mut entries = Vec::new();
+    for level in 1..=campaign_len {
+        let par = levels.level_data(&Level(level)).par;
+        let locked = level > save.unlocked_level;
+        let status = if locked {
+            locale.text("level_select.locked")
+        } else if let Some(best) = save.best_crates.get(&level) {
+            locale.format("level_select.best", &[("best", best), ("par", &par)])
+        } else {
+            locale.format("level_select.par", &[("par", &par)])
+        };
+        let label = locale.format("hud.level", &[("level", &level)]);
+        entries.push((label, status, Some(level), locked));
+    }
+
+    // endless picks up wherever the player got to
+    let endless_level = save.unlocked_level.max(campaign_len + 1);
+    let endless_locked = save.unlocked_level <= campaign_len;
+    entries.push((
+        locale.text("level_select.endless"),
+        if endless_locked {
+            locale.text("level_select.locked")
+        } else {
+            locale.format("hud.level", &[("level", &endless_level)])
+        },
+        Some(endless_level),
+        endless_locked,
+    ));
+    entries.push((locale.text("common.back"), String::new(), None, false));
+
+    let buttons = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, (label, status, level, locked))| {
+            let mut button = commands.spawn_text_button(&label, hue);
+            button.insert((
+                LevelEntry {
+                    index,
+                    level,
+                    locked,
+                },
+                Style {
+                    width: Val::Px(ENTRY_WIDTH),
+                    margin: UiRect::all(Val::Px(ENTRY_MARGIN)),
+                    ..button_style()
+                },
+            ));
+
+            // locked levels can't be hovered or pressed
+            if locked {
+                button
+                    .remove::<Interaction>()
+                    .insert(BackgroundColor(Color::DARK_GRAY));
+            }
+
+            button.with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        status,
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::WHITE.with_a(0.7),
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(8.0),
+                        ..default()
+                    }),
+                );
+            });
+
+            button.id()
+        })
+        .collect::<Vec<_>>();
+
+    let grid = commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px((ENTRY_WIDTH + ENTRY_MARGIN * 2.0) * COLUMNS as f32),
+                flex_wrap: FlexWrap::Wrap,
+                ..default()
+            },
+            ..default()
+        })
+        .push_children(&buttons)
+        .id();
+
+    commands
+        .spawn((LevelSelectPanel, overlay_panel(10)))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    locale.text("level_select.title"),
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+        })
+        .add_child(grid);
+
+    // start on the furthest level the player can play
+    let focus = save.unlocked_level.min(campaign_len + 1) - 1;
+    commands.insert_resource(LevelSelectFocus(focus));
+}
+
+// up, down, left and right move between unlocked entries
+fn navigate_level_select(
+    mut focus: ResMut<LevelSelectFocus>,
+    q_entries: Query<(&LevelEntry, &Interaction)>,
+    q_all_entries: Query<&LevelEntry>,
+    input: Res<PlayerInput>,
+) {
+    // the mouse moves focus too, so keys carry on from wherever it was
+    for (entry, interaction) in q_entries.iter() {
+        if *interaction != Interaction::None && focus.0 != entry.index {
+            focus.0 = entry.index;
+        }
+    }
+
+    let mut step: isize = 0;
+    if input.just_pressed(Action::Left) {
+        step = -1;
+    }
+    if input.just_pressed(Action::Right) {
+        step = 1;
+    }
+    if input.just_pressed(Action::Up) {
+        step = -(COLUMNS as isize);
+    }
+    if input.just_pressed(Action::Down) {
+        step = COLUMNS as isize;
+    }
+    if step == 0 {
+        return;
+    }
+
+    let count = q_all_entries.iter().count() as isize;
+    let is_locked = |index: isize| {
+        q_all_entries
+            .iter()
+            .any(|entry| entry.index as isize == index && entry.locked)
+    };
+
+    // skip over locked entries, stay put at the edges
+    let mut index = focus.0 as isize + step;
+    while (0..count).contains(&index) && is_locked(index) {
+        index += step.signum();
+    }
+    if (0..count).contains(&index) {
+        focus.0 = index as usize;
+    }
+}
+
+fn choose_level(
+    mut commands: Commands,
+    focus: Res<LevelSelectFocus>,
+    q_entries: Query<(&LevelEntry, Option<&Interaction>)>,
+    q_panel: Query<Entity, With<LevelSelectPanel>>,
+    input: Res<PlayerInput>,
+    mut level: ResMut<Level>,
+    levels: Levels,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let confirm = input.just_pressed(Action::Confirm);
+    let back = input.just_pressed(Action::Back);
+
+    let chosen = q_entries.iter().find(|(entry, interaction)| {
+        *interaction == Some(&Interaction::Pressed) || (confirm && entry.index == focus.0)
+    });
+
+    let target = match chosen {
+        Some((entry, _)) if !entry.locked => entry.level,
+        _ if back => None,
+        _ => return,
+    };
+
+    for ent in q_panel.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    commands.remove_resource::<LevelSelectFocus>();
+
+    if let Some(target) = target {
+        level.0 = target;
+        commands.insert_resource(CurrentLevel(levels.level_data(&level)));
+        next_state.set(GameState::ReadyToLaunch);
+    }
+}
+
+fn highlight_focused_entry(
+    focus: Res<LevelSelectFocus>,
+    mut q_entries: Query<(&LevelEntry, &mut BackgroundColor, &mut BorderColor)>,
+    primary_color_hue: Res<PrimaryColorHue>,
+) {
+    for (entry, mut background_color, mut border_color) in q_entries.iter_mut() {
+        if entry.locked {
+            continue;
+        }
+
+        if entry.index == focus.0 {
+            background_color.0 = button_hover_color(primary_color_hue.0);
+            border_color.0 = Color::WHITE.with_a(0.8);
+        } else {
+            background_color.0 = button_background_color(primary_color_hue.0);
+            border_color.0 = Color::BLACK.with_a(0.5);
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use std::{collections::HashMap, fmt::Display};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{button::set_button_text, utils::AssetHandle};
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<StringTable>()
+            .init_asset_loader::<StringTableLoader>()
+            .insert_resource(Locale::default())
+            .insert_resource(AssetHandle::<Locale, Font>::new(Handle::default()))
+            .add_systems(Startup, load_string_tables)
+            .add_systems(
+                Update,
+                (
+                    add_string_tables,
+                    (load_font, relabel_text).run_if(resource_changed::<Locale>()),
+                    apply_font,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// every language there is a table for, the first one is what's missing falls back to
+pub const LANGUAGES: [&str; 3] = ["en", "sv", "ru"];
+
+// all the text in one language, from assets/locale/*.strings.ron
+#[derive(Deserialize, TypeUuid, TypePath, Debug, Clone, Default)]
+#[uuid = "8c3e51d7-2a94-4f0b-b6d1-93e0f7a4c218"]
+pub struct StringTable {
+    // in the language itself, for the picker
+    pub name: String,
+    // for scripts the built in font doesn't have
+    #[serde(default)]
+    pub font: Option<String>,
+    #[serde(default)]
+    pub plural_rule: PluralRule,
+    // `{name}` is replaced by the argument called name
+    #[serde(default)]
+    pub strings: HashMap<String, String>,
+    // like strings, but picked by a count
+    #[serde(default)]
+    pub plurals: HashMap<String, Plural>,
+    // cargo from the catalog, by its english name
+    #[serde(default)]
+    pub cargo: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Plural {
+    #[serde(default)]
+    pub one: Option<String>,
+    #[serde(default)]
+    pub few: Option<String>,
+    #[serde(default)]
+    pub many: Option<String>,
+    pub other: String,
+}
+
+// which form of a word goes with which number, a small part of the cldr rules
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PluralRule {
+    // 1 is one, everything else other
+    #[default]
+    OneOther,
+    // russian, ukrainian: 1, 21, 31 are one, 2-4, 22-24 few, the rest many
+    EastSlavic,
+    // no plurals at all, like japanese
+    Other,
+}
+
+impl PluralRule {
+    fn pick<'a>(&self, plural: &'a Plural, count: usize) -> &'a str {
+        let form = match self {
+            PluralRule::OneOther if count == 1 => &plural.one,
+            PluralRule::EastSlavic => match (count % 10, count % 100) {
+                (1, rem) if rem != 11 => &plural.one,
+                (2..=4, rem) if !(12..=14).contains(&rem) => &plural.few,
+                _ => &plural.many,
+            },
+            _ => &None,
+        };
+        form.as_deref().unwrap_or(&plural.other)
+    }
+}
+
+// the language being shown, and the tables loaded so far
+#[derive(Resource)]
+pub struct Locale {
+    pub language: String,
+    english: StringTable,
+    tables: HashMap<String, StringTable>,
+}
+
+// built in so there's always something to fall back to, even before anything has loaded
+const ENGLISH: &str = include_str!("../assets/locale/en.strings.ron");
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            language: LANGUAGES[0].to_string(),
+            english: ron::from_str(ENGLISH).expect("english strings should parse"),
+            tables: HashMap::new(),
+        }
+    }
+}
+
+pub type Args<'a> = [(&'a str, &'a dyn Display)];
+
+fn fill(template: &str, args: &Args) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+impl Locale {
+    fn table(&self) -> Option<&StringTable> {
+        self.tables.get(&self.language)
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    // english when this language doesn't have it, the key itself when nothing does
+    pub fn format(&self, key: &str, args: &Args) -> String {
+        let template = self
+            .table()
+            .and_then(|table| table.strings.get(key))
+            .or_else(|| self.english.strings.get(key));
+        match template {
+            Some(template) => fill(template, args),
+            None => key.to_string(),
+        }
+    }
+
+    // the form that goes with `count`, which is also there as `{count}`
+    pub fn count(&self, key: &str, count: usize) -> String {
+        let form = [self.table(), Some(&self.english)]
+            .into_iter()
+            .flatten()
+            .find_map(|table| {
+                let plural = table.plurals.get(key)?;
+                Some(table.plural_rule.pick(plural, count))
+            });
+        match form {
+            Some(form) => fill(form, &[("count", &count)]),
+            None => key.to_string(),
+        }
+    }
+
+    // cargo names come from the catalog in english
+    pub fn cargo(&self, name: &str) -> String {
+        self.table()
+            .and_then(|table| table.cargo.get(name))
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    pub fn on_off(&self, on: bool) -> String {
+        self.text(if on { "on" } else { "off" })
+    }
+
+    // what the language calls itself
+    pub fn language_name(&self, language: &str) -> String {
+        match self.tables.get(language) {
+            Some(table) => table.name.clone(),
+            None if language == LANGUAGES[0] => self.english.name.clone(),
+            None => language.to_string(),
+        }
+    }
+
+    // the one after this in the picker, skipping any that haven't loaded
+    pub fn next_language(&self) -> String {
+        let i = LANGUAGES
+            .iter()
+            .position(|language| *language == self.language)
+            .unwrap_or(0);
+        (1..=LANGUAGES.len())
+            .map(|step| LANGUAGES[(i + step) % LANGUAGES.len()])
+            .find(|language| *language == LANGUAGES[0] || self.tables.contains_key(*language))
+            .unwrap_or(LANGUAGES[0])
+            .to_string()
+    }
+}
+
+// the player's own language if there is a table for it
+pub fn system_language() -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let tag = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))?;
+    #[cfg(target_arch = "wasm32")]
+    let tag = web_sys::window()?.navigator().language()?;
+
+    // sv_SE.UTF-8, sv-SE
+    let language = tag.get(..2)?.to_lowercase();
+    LANGUAGES.contains(&language.as_str()).then_some(language)
+}
+
+// text that's one string from the table, kept in the current language.
+// on a button it relabels the button's text
+#[derive(Component)]
+pub struct Localized {
+    key: &'static str,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Localized {
+    pub fn new(key: &'static str) -> Self {
+        Localized {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    pub fn text(&self, locale: &Locale) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect::<Vec<_>>();
+        locale.format(self.key, &args)
+    }
+}
+
+#[derive(Default)]
+struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let table = ron::de::from_bytes::<StringTable>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(table));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct StringTableHandles(Vec<(String, Handle<StringTable>)>);
+
+// english is built in, the others come from assets
+fn load_string_tables(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handles = LANGUAGES[1..]
+        .iter()
+        .map(|language| {
+            let path = format!("locale/{}.strings.ron", language);
+            (language.to_string(), asset_server.load(path))
+        })
+        .collect();
+    commands.insert_resource(StringTableHandles(handles));
+}
+
+// picked up again whenever a file changes, so translators can see their work without a restart
+fn add_string_tables(
+    mut ev_asset: EventReader<AssetEvent<StringTable>>,
+    handles: Res<StringTableHandles>,
+    tables: Res<Assets<StringTable>>,
+    mut locale: ResMut<Locale>,
+) {
+    for event in ev_asset.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            let language = handles.0.iter().find(|(_, other)| other == handle);
+            if let (Some((language, _)), Some(table)) = (language, tables.get(handle)) {
+                locale.tables.insert(language.clone(), table.clone());
+            }
+        }
+    }
+}
+
+fn load_font(
+    locale: Res<Locale>,
+    asset_server: Res<AssetServer>,
+    mut font: ResMut<AssetHandle<Locale, Font>>,
+) {
+    let handle = match locale.table().and_then(|table| table.font.as_ref()) {
+        Some(path) => asset_server.load(path),
+        None => Handle::default(),
+    };
+    if font.handle != handle {
+        font.handle = handle;
+    }
+}
+
+fn relabel_text(
+    locale: Res<Locale>,
+    q_localized: Query<(Entity, &Localized, Option<&Children>)>,
+    mut q_text: Query<&mut Text>,
+) {
+    for (ent, localized, children) in q_localized.iter() {
+        let value = localized.text(&locale);
+        if let Ok(mut text) = q_text.get_mut(ent) {
+            text.sections[0].value = value;
+        } else if let Some(children) = children {
+            set_button_text(children, &mut q_text, &value);
+        }
+    }
+}
+
+// every bit of text uses the current language's font, whoever spawned it
+fn apply_font(font: Res<AssetHandle<Locale, Font>>, mut q_text: Query<&mut Text>) {
+    for mut text in q_text.iter_mut() {
+        if !font.is_changed() && !text.is_added() {
+            continue;
+        }
+        for section in text.sections.iter_mut() {
+            section.style.font = font.handle.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(language: &str) -> StringTable {
+        let path = format!("assets/locale/{}.strings.ron", language);
+        ron::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn every_language_has_every_english_string() {
+        let english = Locale::default().english;
+        for language in LANGUAGES[1..].iter() {
+            let table = load(language);
+            for key in english.strings.keys() {
+                assert!(
+                    table.strings.contains_key(key),
+                    "{} is missing {}",
+                    language,
+                    key
+                );
+            }
+            for key in english.plurals.keys() {
+                assert!(
+                    table.plurals.contains_key(key),
+                    "{} is missing {}",
+                    language,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn missing_strings_fall_back_to_english() {
+        let mut locale = Locale::default();
+        let mut swedish = load("sv");
+        swedish.strings.remove("menu.play");
+        locale.tables.insert("sv".to_string(), swedish);
+        locale.language = "sv".to_string();
+
+        assert_eq!(locale.text("menu.play"), "Play");
+        assert_ne!(
+            locale.text("menu.quit"),
+            Locale::default().text("menu.quit")
+        );
+        assert_eq!(locale.text("no.such.key"), "no.such.key");
+        assert_eq!(
+            locale.format("hud.level", &[("level", &3)]),
+            load("sv").strings["hud.level"].replace("{level}", "3")
+        );
+    }
+
+    #[test]
+    fn counts_pick_the_right_plural() {
+        let plural = Plural {
+            one: Some("one".to_string()),
+            few: Some("few".to_string()),
+            many: Some("many".to_string()),
+            other: "other".to_string(),
+        };
+        let picks = |rule: PluralRule, counts: &[usize]| {
+            counts
+                .iter()
+                .map(|count| rule.pick(&plural, *count))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            picks(PluralRule::OneOther, &[0, 1, 2, 21]),
+            ["other", "one", "other", "other"]
+        );
+        assert_eq!(
+            picks(PluralRule::EastSlavic, &[1, 3, 5, 11, 12, 21, 22, 25, 111]),
+            ["one", "few", "many", "many", "many", "one", "few", "many", "many"]
+        );
+        assert_eq!(picks(PluralRule::Other, &[1, 2]), ["other", "other"]);
+
+        let locale = Locale::default();
+        assert_eq!(locale.count("menu.finished", 1), "Finished using 1 crate!");
+        assert_eq!(locale.count("menu.finished", 4), "Finished using 4 crates!");
+    }
+}
@@ -1,9 +1,11 @@
+// systems ask for every resource and query they touch, however many and however nested
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 use std::{f32::consts::PI, time::Duration};
 
 use bevy::{
     app::AppExit,
     asset::ChangeWatcher,
-    audio::{PlaybackMode, Volume, VolumeLevel},
     core_pipeline::{bloom::BloomSettings, tonemapping::Tonemapping},
     math::{vec2, vec3, Vec3Swizzles},
     prelude::*,
@@ -12,26 +14,67 @@ use bevy::{
         camera::ScalingMode,
         render_resource::{AddressMode, AsBindGroup, SamplerDescriptor, ShaderRef},
     },
-    sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle},
-    time::Stopwatch,
-    window::PrimaryWindow,
 };
-use button::{interact_button, ButtonCommands};
-// use mute::MuteButtonPlugin;
+use accessibility::{Accessibility, AccessibilityPlugin};
+use button::{interact_button, overlay_open, ButtonCommands};
+use camera::{CameraDirectorPlugin, CameraShake, DirectedCamera, VIEW_HEIGHT};
+use cargo::CargoPlugin;
+use controls::{Action, Bindings, ControlsPlugin, PlayerInput};
+use gameplay::{
+    Aim, BodyModel, Cannon, CargoSound, CrashEvents, Crate, CrateLaunched, CurrentCrate,
+    CurrentLevel, Debris, DebrisCollected, Earth, EarthDestroyed, GameState, GameplayPlugin,
+    LaunchPower, Level, LevelBody, LevelCleared, Mercury, OnHit, PickedUp, RestartLevel, Rotation,
+    Score, Sun, Velocity, CANNON_RADIUS, CRATE_SPIN,
+};
+use endless::EndlessPlugin;
+use kill_log::{KillLog, KillLogPlugin};
+use level::{LevelPlugin, Levels};
+use locale::{Locale, LocalePlugin, Localized};
+use level_select::LevelSelectPlugin;
+use mixer::{mixed_audio, Channel, Mixed, Mixer, MixerPlugin};
+use mute::MuteButtonPlugin;
+use particles::{ParticleEmitter, ParticlePool, ParticlesPlugin};
+use pause::{PausePlugin, PauseState};
+use rebind::RebindPlugin;
+use replay::{is_replaying, ReplayPlugin};
+use save::SavePlugin;
+use scorecard::ScorecardPlugin;
+use settings::SettingsPlugin;
+use trajectory::TrajectoryPreviewPlugin;
 
 #[cfg(feature = "dev")]
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use rand::seq::SliceRandom;
 use utils::AssetHandle;
 
+mod accessibility;
 mod button;
-// mod mute;
+mod camera;
+mod cargo;
+mod controls;
+mod endless;
+mod gameplay;
+mod kill_log;
+mod level;
+mod level_select;
+mod locale;
+mod mixer;
+mod mute;
+mod particles;
+mod pause;
+mod rebind;
+mod replay;
+mod save;
+mod scorecard;
+mod settings;
+mod storage;
+mod trajectory;
 mod utils;
 
 static PRIMARY_COLOR_HUE: f32 = 0.59;
 // static PRIMARY_COLOR_HUE: f32 = 0.8;
 static MENU_MUSIC_VOLUME: f32 = 0.5;
 static PLAYING_MUSIC_VOLUME: f32 = 0.8;
+static PAUSED_MUSIC_VOLUME: f32 = 0.25;
 
 fn main() {
     let mut app = App::new();
@@ -61,73 +104,116 @@ fn main() {
             }),
     )
     .insert_resource(ClearColor(Color::hsl(PRIMARY_COLOR_HUE * 360.0, 0.2, 0.1)))
-    .insert_resource(Score(0))
-    .insert_resource(Level(1))
-    .insert_resource(FixedTime::new_from_secs(1.0 / 60.0))
-    .insert_resource(LaunchPower(Stopwatch::new()))
     .insert_resource(PrimaryColorHue(PRIMARY_COLOR_HUE))
-    .insert_resource(KillLog(Vec::new()))
-    .insert_resource(CameraShake(1.0))
     .add_plugins(MaterialPlugin::<SunMaterial>::default())
     .add_plugins(MaterialPlugin::<BackgroundMaterial>::default())
-    // .add_plugins(MuteButtonPlugin)
-    .add_state::<GameState>()
+    .add_plugins(LocalePlugin)
+    .add_plugins(GameplayPlugin)
+    .add_plugins(CameraDirectorPlugin)
+    .add_plugins(ControlsPlugin)
+    .add_plugins(LevelPlugin)
+    .add_plugins(CargoPlugin)
+    .add_plugins(EndlessPlugin)
+    .add_plugins(LevelSelectPlugin)
+    .add_plugins(TrajectoryPreviewPlugin)
+    .add_plugins(ReplayPlugin)
+    .add_plugins(SavePlugin)
+    .add_plugins(ScorecardPlugin)
+    .add_plugins(KillLogPlugin)
+    .add_plugins(PausePlugin)
+    .add_plugins(SettingsPlugin)
+    .add_plugins(AccessibilityPlugin)
+    .add_plugins(RebindPlugin)
+    .add_plugins(ParticlesPlugin)
+    .add_plugins(MixerPlugin)
+    .add_plugins(MuteButtonPlugin)
     .add_systems(Startup, setup)
-    .add_systems(OnEnter(GameState::Menu), on_enter_menu)
-    .add_systems(OnEnter(GameState::Launched), on_enter_launched)
+    .add_systems(
+        OnEnter(GameState::Menu),
+        (
+            on_enter_menu,
+            refresh_menu,
+            spawn_quit_button,
+            stop_charge_sound,
+        ),
+    )
+    .add_systems(RestartLevel, stop_charge_sound)
+    .add_systems(OnEnter(PauseState::Paused), duck_music)
+    .add_systems(OnExit(PauseState::Paused), restore_music)
     .add_systems(OnEnter(GameState::ChargingLaunch), on_enter_charging)
-    .add_systems(OnExit(GameState::Menu), (on_exit_menu, on_enter_playing))
+    .add_systems(
+        OnExit(GameState::Menu),
+        (
+            on_exit_menu,
+            on_enter_playing,
+            despawn_play_button,
+            despawn_quit_button,
+        ),
+    )
     .add_systems(OnEnter(GameState::ReadyToLaunch), on_enter_ready)
-    // .add_systems(OnExit(GameState::ReadyToLaunch), on_exit_playing)
-    .add_systems(FixedUpdate, (apply_gravity))
     .add_systems(
         Update,
         (
-            increase_crate_mass,
-            spawn_crate_trail,
-            orbit_debris,
-            remove_crate_on_sun_collision,
-            remove_crate_on_mercury_collision,
-        )
-            .run_if(in_state(GameState::Launched)),
+            add_models,
+            start_cargo_sounds,
+            on_crate_launched,
+            on_debris_picked_up,
+            play_crash_sound,
+            shake_camera_on_crash,
+            spawn_crash_explosions,
+            hide_destroyed_earth,
+            on_level_cleared,
+            apply_reduced_motion.run_if(resource_changed::<Accessibility>()),
+            update_kill_log_text
+                .run_if(resource_changed::<KillLog>().or_else(resource_changed::<Locale>())),
+        ),
     )
     .add_systems(
         Update,
         (
-            exit_on_esc.run_if(is_desktop),
             interact_button,
-            always,
             spin_earth,
             spin_debris,
-            // spin_crates,
+            aim_cannon.run_if(not(is_replaying).and_then(in_state(PauseState::Running))),
             update_cannon_transform,
             rotate_crates,
-            apply_velocity,
-            // apply_gravity,
-            // remove_crate_on_sun_collision,
-            remove_crate_on_earth_collision,
-            fade_explosions,
-            update_camera_position,
-            attach_debris_to_crate_collision,
             update_scream_speed,
-            orbit_mercury,
         ),
     )
     .add_systems(
         Update,
-        (start_launching, orbit_debris).run_if(in_state(GameState::ReadyToLaunch)),
+        (start_launching,).run_if(
+            in_state(GameState::ReadyToLaunch)
+                .and_then(in_state(PauseState::Running))
+                .and_then(not(is_replaying)),
+        ),
     )
     .add_systems(
         Update,
-        (update_launch_power, launch, orbit_debris).run_if(in_state(GameState::ChargingLaunch)),
+        (launch,).run_if(
+            in_state(GameState::ChargingLaunch)
+                .and_then(in_state(PauseState::Running))
+                .and_then(not(is_replaying)),
+        ),
     )
     .add_systems(
         Update,
-        (interact_play_button,).run_if(in_state(GameState::Menu)),
+        (
+            interact_play_button,
+            play_with_keys.run_if(not(overlay_open)),
+            interact_quit_button,
+        )
+            .run_if(in_state(GameState::Menu)),
     )
     .add_systems(
         Update,
-        (while_playing,).run_if(in_state(GameState::ReadyToLaunch)),
+        refresh_menu.run_if(
+            in_state(GameState::Menu).and_then(
+                resource_changed::<Level>()
+                    .or_else(resource_changed::<Accessibility>())
+                    .or_else(resource_changed::<Locale>()),
+            ),
+        ),
     );
 
     // #[cfg(feature = "dev")]
@@ -136,26 +222,16 @@ fn main() {
     app.run();
 }
 
-#[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default)]
-enum GameState {
-    #[default]
-    Menu,
-    ReadyToLaunch,
-    ChargingLaunch,
-    Launched,
-}
-
 #[derive(Component)]
 struct PlayButton;
 
+#[derive(Component)]
+struct QuitButton;
+
 #[derive(Reflect, Resource, Default)]
 #[reflect(Resource)]
 pub struct PrimaryColorHue(f32);
 
-fn is_desktop() -> bool {
-    std::env::consts::OS == "macos" || std::env::consts::OS == "windows"
-}
-
 #[derive(Component)]
 struct Music;
 
@@ -177,43 +253,35 @@ struct InfoText;
 #[derive(Component)]
 struct InstructionText;
 
-#[derive(Resource)]
-struct KillLog(Vec<String>);
-
 #[derive(Component)]
 struct KillLogText;
 
-#[derive(Component)]
-struct Explosion;
-
-#[derive(Resource)]
-struct CameraShake(f32);
-
 // #[derive(Resource)]
 // struct ExplosionMesh(Option<Handle<Mesh>>);
 
 // #[derive(Resource)]
 // struct ExplosionMaterial(Option<Handle<ColorMaterial>>);
 
+// a crate's looping sound, following the crate it belongs to
 #[derive(Component)]
-struct WhiningSound;
+struct WhiningSound(Entity);
 
 fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
     mut std_materials: ResMut<Assets<StandardMaterial>>,
     mut sun_materials: ResMut<Assets<SunMaterial>>,
     mut bg_materials: ResMut<Assets<BackgroundMaterial>>,
     asset_server: Res<AssetServer>,
     level: Res<Level>,
-    score: Res<Score>,
+    mixer: Res<Mixer>,
+    locale: Res<Locale>,
 ) {
     // spawn kill text
     commands.spawn((
         KillLogText,
         TextBundle::from_section(
-            "Incineration log:".to_string(),
+            locale.text("hud.kill_log"),
             TextStyle {
                 font_size: 20.0,
                 color: Color::WHITE,
@@ -227,26 +295,11 @@ fn setup(
         }),
     ));
 
-    // whining
-    commands.spawn((
-        AudioBundle {
-            source: asset_server.load("aaa.ogg"),
-            settings: PlaybackSettings {
-                paused: false,
-                mode: PlaybackMode::Loop,
-                volume: Volume::Relative(VolumeLevel::new(0.0)),
-                ..default()
-            },
-            ..default()
-        },
-        WhiningSound,
-    ));
-
     // spawn score text
     commands.spawn((
         ScoreText,
         TextBundle::from_section(
-            format!("Level {}", level.0),
+            locale.format("hud.level", &[("level", &level.0)]),
             TextStyle {
                 font_size: 64.0,
                 color: Color::WHITE,
@@ -262,15 +315,13 @@ fn setup(
 
     // music
     commands.spawn((
-        AudioBundle {
-            source: asset_server.load("music.ogg"),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
-                volume: Volume::Relative(VolumeLevel::new(MENU_MUSIC_VOLUME)),
-                ..default()
-            },
-            ..default()
-        },
+        mixed_audio(
+            asset_server.load("music.ogg"),
+            Channel::Music,
+            MENU_MUSIC_VOLUME,
+            PlaybackSettings::LOOP,
+            &mixer,
+        ),
         Music,
     ));
 
@@ -329,7 +380,7 @@ fn setup(
             tonemapping: Tonemapping::None,
             projection: Projection::Orthographic(OrthographicProjection {
                 viewport_origin: vec2(0.5, 0.5),
-                scaling_mode: ScalingMode::FixedVertical(720.0),
+                scaling_mode: ScalingMode::FixedVertical(VIEW_HEIGHT),
                 scale: 0.12,
                 ..default()
             }),
@@ -341,6 +392,7 @@ fn setup(
             intensity: 0.18,
             ..default()
         },
+        DirectedCamera::default(),
     ));
 
     // AssetHandle example
@@ -363,18 +415,6 @@ fn setup(
         asset_server.load("debris.glb#Scene0"),
     ));
 
-    // explosion asset handles
-    commands.insert_resource(AssetHandle::<Explosion, Mesh>::new(
-        meshes.add(shape::Circle::new(1.0).into()).into(),
-    ));
-    commands.insert_resource(AssetHandle::<Explosion, StandardMaterial>::new(
-        std_materials.add(StandardMaterial {
-            base_color: Color::ORANGE_RED * 20.0,
-            unlit: true,
-            ..default()
-        }),
-    ));
-
     // // spawn stars.png texture
     // commands.spawn(MaterialMeshBundle {
     //     mesh: meshes.add(shape::Plane::from_size(100.0).into()).into(),
@@ -386,9 +426,10 @@ fn setup(
 
     // spawn background
     commands.spawn(MaterialMeshBundle {
-        mesh: meshes.add(shape::Plane::from_size(1000.0).into()).into(),
+        mesh: meshes.add(shape::Plane::from_size(1000.0).into()),
         material: bg_materials.add(BackgroundMaterial {
             color: Color::WHITE,
+            motion: 1.0,
             color_texture: asset_server.load("stars.png"),
         }),
         transform: Transform::from_translation(vec3(0.0, 0.0, -100.0))
@@ -428,54 +469,40 @@ fn setup(
     //     ..default()
     // }, Logo));
 
-    // spawn sun
-    commands.spawn((
-        MaterialMeshBundle {
-            mesh: meshes.add(shape::Plane::from_size(30.0).into()).into(),
-            material: sun_materials.add(SunMaterial {
-                color: Color::ORANGE_RED,
-                color_texture: asset_server.load("noise.png"),
-            }),
-            transform: Transform::from_translation(vec3(0.0, 15.0, -50.0))
-                .with_rotation(Quat::from_rotation_x(PI / 2.0)),
-            ..default()
-        },
-        Sun,
+    // sun asset handles
+    commands.insert_resource(AssetHandle::<Sun, Mesh>::new(
+        meshes.add(shape::Plane::from_size(30.0).into()),
     ));
+    commands.insert_resource(AssetHandle::<Sun, SunMaterial>::new(sun_materials.add(
+        SunMaterial {
+            color: Color::ORANGE_RED,
+            motion: 1.0,
+            color_texture: asset_server.load("noise.png"),
+        },
+    )));
 
     // spawn point light
-    commands.spawn(PointLightBundle {
-        transform: Transform::from_translation(vec3(0.0, 15.0, 0.0)),
-        point_light: PointLight {
-            intensity: 1000000.0,
-            range: 1000.0,
-            color: Color::rgb(1.0, 0.8, 0.5),
+    commands.spawn((
+        PointLightBundle {
+            transform: Transform::from_translation(vec3(0.0, 15.0, 0.0)),
+            point_light: PointLight {
+                intensity: 1000000.0,
+                range: 1000.0,
+                color: Color::rgb(1.0, 0.8, 0.5),
+                ..default()
+            },
             ..default()
         },
-        ..default()
-    });
+        SunLight,
+    ));
 
-    // spawn earth
-    commands
-        .spawn(SceneBundle {
-            scene: asset_server.load("earth.glb#Scene0"),
-            transform: Transform::from_xyz(0.0, -25.0, -20.0)
-                .with_scale(Vec3::splat(5.0))
-                .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
-            ..Default::default()
-        })
-        .insert(Earth);
-
-    // spawn mercury
-    commands
-        .spawn(SceneBundle {
-            scene: asset_server.load("mercury.glb#Scene0"),
-            transform: Transform::from_xyz(0.0, -10.0, -20.0)
-                .with_scale(Vec3::splat(3.0))
-                .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
-            ..Default::default()
-        })
-        .insert(Mercury);
+    // load planet models
+    commands.insert_resource(AssetHandle::<Earth, Scene>::new(
+        asset_server.load("earth.glb#Scene0"),
+    ));
+    commands.insert_resource(AssetHandle::<Mercury, Scene>::new(
+        asset_server.load("mercury.glb#Scene0"),
+    ));
 
     // // spawn explosion
     // commands
@@ -490,20 +517,12 @@ fn setup(
     //         ..default()
     //     }, Explosion));
 
-    // spawn cannon + crate
-    commands.spawn((
-        Cannon,
-        SceneBundle {
-            scene: asset_server.load("launcher.glb#Scene0"),
-            transform: Transform::from_xyz(0.0, 0.0, 0.0)
-                .with_scale(Vec3::splat(1.0))
-                .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
-            ..default()
-        },
-        // SpatialBundle {
-        //     transform: Transform::from_translation(vec3(0.0, 0.0, 0.0)),
-        //     ..default()
-        // },
+    // load cannon + crate models
+    commands.insert_resource(AssetHandle::<Cannon, Scene>::new(
+        asset_server.load("launcher.glb#Scene0"),
+    ));
+    commands.insert_resource(AssetHandle::<Crate, Scene>::new(
+        asset_server.load("crate.glb#Scene0"),
     ));
     // .with_children(|parent| {
     //     parent.spawn((
@@ -527,141 +546,13 @@ struct SlorpSound;
 #[derive(Component)]
 struct SuccessSound;
 
-// mercury orbit the sun
-fn orbit_mercury(
-    time: Res<Time>,
-    mut q_mercury: Query<&mut Transform, (With<Mercury>, Without<Sun>)>,
-    q_sun: Query<&Transform, (With<Sun>, Without<Debris>)>,
-    level: Res<Level>,
-) {
-    // let speed = ((level.0 as f32 - 3.0).max(0.0) * 0.1).min(0.3);
-    let speed = 0.5;
-    let mut radius = 23.0 + (time.elapsed_seconds() * 0.41 + 1.0).sin() * 8.0;
-    if level.0 < 5 {
-        radius = 200.0;
-    }
-
-    for mut mercury_transform in q_mercury.iter_mut() {
-        if let Ok(sun_transform) = q_sun.get_single() {
-            let sun_pos = sun_transform.translation.xy();
-            let mercury_pos = mercury_transform.translation.xy();
-            // let distance = sun_pos.distance(mercury_pos);
-            let direction = (sun_pos - mercury_pos).normalize();
-            let direction_angle = direction.y.atan2(direction.x);
-            let distance = radius;
-
-            // move position along direction_angle
-            let new_direction_angle = direction_angle + time.delta_seconds() * speed;
-            let new_direction = Vec2::new(new_direction_angle.cos(), new_direction_angle.sin());
-            let new_position = sun_pos - new_direction * distance;
-            mercury_transform.translation = vec3(new_position.x, new_position.y, 0.0);
-
-            mercury_transform.rotation = Quat::from_euler(
-                EulerRot::XYZ,
-                0.7,
-                time.elapsed_seconds() * 0.2,
-                time.elapsed_seconds(),
-            );
-        }
-    }
-}
-
-// debris orbit the sun
-fn orbit_debris(
-    time: Res<Time>,
-    mut q_debris: Query<&mut Transform, (With<Debris>, Without<PickedUp>)>,
-    q_sun: Query<&Transform, (With<Sun>, Without<Debris>)>,
-    level: Res<Level>,
-) {
-    return; // Spinning debris = free win.. :P
-
-    let speed = ((level.0 as f32 - 3.0).max(0.0) * 0.1).min(0.3);
-
-    for mut transform in q_debris.iter_mut() {
-        if let Ok(sun_transform) = q_sun.get_single() {
-            let sun_pos = sun_transform.translation.xy();
-            let debris_pos = transform.translation.xy();
-            let distance = sun_pos.distance(debris_pos);
-            let direction = (sun_pos - debris_pos).normalize();
-            let direction_angle = direction.y.atan2(direction.x);
-
-            // move position along direction_angle
-            let new_direction_angle = direction_angle + time.delta_seconds() * speed;
-            let new_direction = Vec2::new(new_direction_angle.cos(), new_direction_angle.sin());
-            let new_position = sun_pos - new_direction * distance;
-            transform.translation = vec3(new_position.x, new_position.y, 0.0);
-        }
-    }
-}
-
-fn spawn_crate_trail(
-    mut commands: Commands,
-    mut q_crate: Query<&Transform, With<Crate>>,
-    time: Res<Time>,
-    mut last_spawned: Local<Option<Duration>>,
-    explosion_mesh: Res<AssetHandle<Explosion, Mesh>>,
-    explosion_mtl: Res<AssetHandle<Explosion, StandardMaterial>>,
-) {
-    if let Some(last_spawned) = last_spawned.as_mut() {
-        if time.elapsed_seconds() - last_spawned.as_secs_f32() < 0.1 {
-            return;
-        }
-    }
-
-    if let Ok(crate_transform) = q_crate.get_single() {
-        let crate_pos = crate_transform.translation.xy();
-
-        // update last_spawned
-        *last_spawned = Some(time.elapsed());
-
-        commands.spawn((
-            PbrBundle {
-                mesh: explosion_mesh.handle.clone().into(),
-                material: explosion_mtl.handle.clone().into(),
-                transform: Transform::from_xyz(crate_pos.x, crate_pos.y, 1.0)
-                    .with_scale(Vec3::splat((0.01 + rand::random::<f32>()) * 0.3)),
-                ..default()
-            },
-            Explosion,
-            Velocity(vec2(
-                rand::random::<f32>() - 0.5,
-                rand::random::<f32>() - 0.5,
-            )),
-        ));
-    }
-}
-
-fn increase_crate_mass(mut q_crate: Query<&mut Mass, With<Crate>>, time: Res<Time>) {
-    for mut mass in q_crate.iter_mut() {
-        mass.0 += time.delta_seconds() * 0.35;
-    }
-}
-
-// scale down explosions in size, and remove when small enough
-fn fade_explosions(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut q_explosion: Query<(Entity, &mut Transform), With<Explosion>>,
-) {
-    for (ent, mut transform) in q_explosion.iter_mut() {
-        let scale = transform.scale;
-
-        let n = time.delta_seconds() * 2.0;
-        let new_scale = scale.lerp(Vec3::ZERO, n);
-
-        transform.scale = new_scale;
-        if new_scale.x < 0.01 {
-            commands.entity(ent).despawn_recursive();
-        }
-    }
-}
-
 fn on_enter_ready(
     mut commands: Commands,
-    q_cannon: Query<Entity, With<Cannon>>,
-    asset_server: Res<AssetServer>,
     level: Res<Level>,
     q_logo: Query<Entity, With<Logo>>,
+    input: Res<PlayerInput>,
+    bindings: Res<Bindings>,
+    locale: Res<Locale>,
 ) {
     // despawn logo
     for ent in q_logo.iter() {
@@ -673,7 +564,7 @@ fn on_enter_ready(
         commands.spawn((
             InstructionText,
             TextBundle::from_section(
-                "Hold down mouse button to fire",
+                input.charge_prompt(&bindings, &locale),
                 TextStyle {
                     font_size: 28.0,
                     color: Color::WHITE,
@@ -687,319 +578,150 @@ fn on_enter_ready(
             }),
         ));
     }
-
-    // things that humanity fires into the sun
-    let crate_strings = vec![
-        "Car tires",
-        "Nuclear waste",
-        "Plastic bottles",
-        "Paper straws",
-        "Cigarette butts",
-        "Aerosol cans",
-        "Razor blades",
-        "Poor fella",
-        "Poor fella",
-        "Dead memes",
-        "Old phones",
-        "Broken eggs",
-        "My mental health",
-    ];
-
-    let random_string = crate_strings
-        .choose(&mut rand::thread_rng())
-        .unwrap()
-        .to_string();
-
-    // spawn crate in cannon
-    for cannon_ent in q_cannon.iter() {
-        commands.entity(cannon_ent).with_children(|parent| {
-            parent.spawn((
-                SceneBundle {
-                    scene: asset_server.load("crate.glb#Scene0"),
-                    transform: Transform::from_xyz(0.0, 3.0, 0.0)
-                        .with_scale(Vec3::splat(1.0))
-                        .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
-                    ..default()
-                },
-                Crate(random_string.clone()),
-                Mass(0.5),
-                CurrentCrate,
-            ));
-        });
-    }
 }
 
 #[derive(Component)]
-struct Earth;
-
-#[derive(Component)]
-struct Mercury;
-
-#[derive(Component)]
-struct Debris;
-
-#[derive(Component)]
-struct OriginalTransform(Transform);
-
-#[derive(Component)]
-struct Sun;
+struct SunLight;
 
 #[derive(Component)]
 struct Logo;
 
-#[derive(Component)]
-struct Cannon;
-
-#[derive(Component)]
-struct Crate(String);
-
-#[derive(Component)]
-struct CurrentCrate;
-
-#[derive(Component)]
-struct Velocity(Vec2);
-
-#[derive(Component)]
-struct Mass(f32);
-
-// if in state ReadyToLaunch & LMB pressed, go to ChargingLaunch
-fn start_launching(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
-    mouse_button_input: Res<Input<MouseButton>>,
-    touches: Res<Touches>,
-    mut launch_power: ResMut<LaunchPower>,
-) {
-    if mouse_button_input.just_pressed(MouseButton::Left) || touches.any_just_pressed() {
+// if in state ReadyToLaunch & charge pressed, go to ChargingLaunch
+fn start_launching(mut next_state: ResMut<NextState<GameState>>, input: Res<PlayerInput>) {
+    if input.just_pressed(Action::Charge) {
         next_state.set(GameState::ChargingLaunch);
-
-        // reset launch_power
-        launch_power.0.reset();
     }
 }
 
-fn update_launch_power(
-    mut launch_power: ResMut<LaunchPower>,
-    time: Res<Time>,
-    mut next_state: ResMut<NextState<GameState>>,
+fn on_enter_charging(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mixer: Res<Mixer>,
 ) {
-    launch_power.0.tick(time.delta());
-
-    // if launch_power > 1.0, go to Launched
-    if launch_power.0.elapsed_secs() > 2.0 {
-        next_state.set(GameState::Launched);
-    }
-}
-
-fn on_enter_charging(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn((
-        AudioBundle {
-            source: asset_server.load("charge.ogg"),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Despawn,
-                volume: Volume::Relative(VolumeLevel::new(0.4)),
-                paused: false,
-                ..default()
-            },
-            ..default()
-        },
+        mixed_audio(
+            asset_server.load("charge.ogg"),
+            Channel::Sfx,
+            0.4,
+            PlaybackSettings::DESPAWN,
+            &mixer,
+        ),
         ChargeSound,
     ));
 }
 
-fn on_enter_launched(
-    asset_server: Res<AssetServer>,
+// a charge cut short by restarting or leaving the level
+fn stop_charge_sound(
     mut commands: Commands,
-    mut launch_power: ResMut<LaunchPower>,
-    mut current_crate: Query<
-        (Entity, &mut Transform, &GlobalTransform),
-        (With<CurrentCrate>, Without<Cannon>, Without<Earth>),
-    >,
-    cannon: Query<&Transform, (With<Cannon>, Without<Earth>)>,
-    earth: Query<&Transform, (With<Earth>, Without<Cannon>)>,
-    charge_sound_controller: Query<(Entity, &AudioSink), With<ChargeSound>>,
-    whining_controller: Query<&AudioSink, With<WhiningSound>>,
-    mut camera_shake: ResMut<CameraShake>,
-    mut score: ResMut<Score>,
+    q_charge_sound: Query<(Entity, Option<&AudioSink>), With<ChargeSound>>,
 ) {
-    // shake camera
-    camera_shake.0 = 0.5 + launch_power.0.elapsed_secs() * 0.8;
-
-    // set whining speed
-    for sink in whining_controller.iter() {
-        // sink.play();
-        sink.set_speed(0.1);
-        // sink.set_volume(0.9);
-    }
-
-    for (ent, sink) in charge_sound_controller.iter() {
-        sink.stop();
+    for (ent, sink) in q_charge_sound.iter() {
+        if let Some(sink) = sink {
+            sink.stop();
+        }
         commands.entity(ent).despawn_recursive();
     }
-
-    commands.spawn((
-        AudioBundle {
-            source: asset_server.load("fired.ogg"),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Despawn,
-                volume: Volume::Relative(VolumeLevel::new(0.4)),
-                paused: false,
-                ..default()
-            },
-            ..default()
-        },
-        FireSound,
-    ));
-
-    let (crate_ent, mut crate_transform, crate_global_transform) = current_crate.single_mut();
-    let cannon_transform = cannon.single().clone();
-    let earth_transform = earth.single().clone();
-    let translation_diff = cannon_transform.translation - earth_transform.translation;
-    let diff_normal = translation_diff.normalize().xy();
-
-    // add Velocity to current crate
-    let power = launch_power.0.elapsed_secs() * 1.5;
-    commands
-        .entity(crate_ent)
-        .insert(Velocity(diff_normal * power));
-
-    // add cannon translation to crate
-    crate_transform.translation = crate_global_transform.translation();
-    crate_transform.rotation = cannon_transform.rotation * crate_transform.rotation;
-
-    // move current_crate from parent to root
-    commands.entity(crate_ent).remove::<Parent>();
-
-    // reset launch_power
-    launch_power.0.reset();
-
-    // increase score
-    score.0 += 1;
 }
 
-// if in state ChargingLaunch & LMB not pressed, go to Launched
-fn launch(
+fn on_crate_launched(
+    asset_server: Res<AssetServer>,
     mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
-    mouse_button_input: Res<Input<MouseButton>>,
-    current_crate: Query<Entity, With<CurrentCrate>>,
-    launch_power: Res<LaunchPower>,
-    touches: Res<Touches>,
-) {
-    if mouse_button_input.just_released(MouseButton::Left) || touches.any_just_released() {
-        next_state.set(GameState::Launched);
-    }
-}
-
-fn apply_gravity(
-    // time: Res<Time>,
-    mut q_crate: Query<(&mut Velocity, &Transform, &Mass), With<Crate>>,
-    q_sun: Query<&Transform, (With<Sun>, Without<Crate>, Without<Mercury>)>,
-    q_earth: Query<&Transform, (With<Earth>, Without<Cannon>, Without<Sun>, Without<Mercury>)>,
-    q_mercury: Query<&Transform, (With<Mercury>, Without<Cannon>, Without<Sun>, Without<Earth>)>,
+    mut ev_launched: EventReader<CrateLaunched>,
+    q_current_crate: Query<Entity, With<CurrentCrate>>,
+    charge_sound_controller: Query<(Entity, &AudioSink), With<ChargeSound>>,
+    whining_controller: Query<&AudioSink, With<WhiningSound>>,
+    mut camera_shake: ResMut<CameraShake>,
+    mixer: Res<Mixer>,
 ) {
-    for (mut velocity, crate_transform, mass) in q_crate.iter_mut() {
-        for sun_transform in q_sun.iter() {
-            let sun_pos = sun_transform.translation;
-            let crate_pos = crate_transform.translation;
-            let distance = sun_pos.distance(crate_pos);
-            let direction = (sun_pos - crate_pos).normalize();
-            let gravity = (direction * 100.0 * mass.0) / distance.powi(2);
-            velocity.0 += Vec2::new(gravity.x, gravity.y);
+    for launched in ev_launched.iter() {
+        // shake camera
+        camera_shake.0 = 0.5 + launched.power * 0.8;
+
+        // leave a trail of fire behind it
+        for crate_ent in q_current_crate.iter() {
+            commands
+                .entity(crate_ent)
+                .insert(ParticleEmitter::crate_trail());
         }
 
-        for earth_transform in q_earth.iter() {
-            let sun_pos = earth_transform.translation;
-            let crate_pos = crate_transform.translation;
-            let distance = sun_pos.distance(crate_pos);
-            let direction = (sun_pos - crate_pos).normalize();
-            let gravity = (direction * 3.0 * mass.0) / distance.powi(2);
-            velocity.0 += Vec2::new(gravity.x, gravity.y);
+        // set whining speed
+        for sink in whining_controller.iter() {
+            sink.set_speed(0.1);
         }
 
-        for mercury_transform in q_mercury.iter() {
-            let sun_pos = mercury_transform.translation;
-            let crate_pos = crate_transform.translation;
-            let distance = sun_pos.distance(crate_pos);
-            let direction = (sun_pos - crate_pos).normalize();
-            let gravity = (direction * 3.0 * mass.0) / distance.powi(2);
-            velocity.0 += Vec2::new(gravity.x, gravity.y);
+        for (ent, sink) in charge_sound_controller.iter() {
+            sink.stop();
+            commands.entity(ent).despawn_recursive();
         }
+
+        commands.spawn((
+            mixed_audio(
+                asset_server.load("fired.ogg"),
+                Channel::Sfx,
+                0.4,
+                PlaybackSettings::DESPAWN,
+                &mixer,
+            ),
+            FireSound,
+        ));
     }
 }
 
-fn apply_velocity(mut q_crate: Query<(&mut Transform, &Velocity)>, time: Res<Time>) {
-    for (mut transform, velocity) in q_crate.iter_mut() {
-        transform.translation +=
-            Vec3::new(velocity.0.x, velocity.0.y, 0.0) * time.delta_seconds() * 20.0;
+// if in state ChargingLaunch & charge released, go to Launched
+fn launch(mut next_state: ResMut<NextState<GameState>>, input: Res<PlayerInput>) {
+    if input.just_released(Action::Charge) {
+        next_state.set(GameState::Launched);
+    }
+}
+
+// point the cannon wherever the player is aiming, with whatever they aim with
+fn aim_cannon(mut aim: ResMut<Aim>, input: Res<PlayerInput>) {
+    if let Some(angle) = input.aim {
+        aim.0 = angle;
     }
 }
 
-// based on cursor position, move cannon in an arc around earth
+// move cannon in an arc around earth, following the aim
 fn update_cannon_transform(
     mut q_cannon: Query<&mut Transform, With<Cannon>>,
     q_earth: Query<&Transform, (With<Earth>, Without<Cannon>)>,
-    // mut mouse_pos: EventReader<CursorMoved>,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
+    aim: Res<Aim>,
     time: Res<Time>,
-    camera_q: Query<(&Camera, &GlobalTransform)>,
     launch_power: Res<LaunchPower>,
-    touches: Res<Touches>,
 ) {
-    let (camera, camera_transform) = camera_q.single();
-
-    if let Ok(mut window) = primary_window.get_single() {
-        let fallback_cursor_pos = Vec2::new(window.width() as f32 / 2.0, window.height() as f32 / 2.0);
-
-        let cursor = if let Some(touch) = touches.first_pressed_position() {
-            Some(touch)
-        } else {
-            window.cursor_position()
-        };
-
-        let cursor = cursor.unwrap_or(fallback_cursor_pos);
-
-        for mut transform in q_cannon.iter_mut() {
-            let earth_transform = q_earth.single();
-            if let Some(cursor_world_pos) = camera
-                .viewport_to_world(camera_transform, cursor)
-                .map(|ray| ray.origin.truncate())
-            {
-                let offset = cursor_world_pos - earth_transform.translation.xy();
-                let normal = offset.normalize();
-                let radius = 6.0;
-                let x = normal.x * radius;
-                let y = normal.y * radius;
-
-                let n = time.delta_seconds() * 16.0;
-
-                let current_translation = transform.translation;
-                let target_translation = Vec3::new(x, y, 0.0) + earth_transform.translation;
-                let current_rotation = transform.rotation;
-                //lookat
-                let target_rotation = Quat::from_rotation_z(-normal.x.atan2(normal.y));
-                let new_translation = current_translation.lerp(target_translation, n);
-                let new_rotation = current_rotation.lerp(target_rotation, n);
-
-                // also, rotate based on launch power
-                let power = launch_power.0.elapsed_secs() * 1.35;
-                let rotation = Quat::from_rotation_y(power * 0.5);
-                let new_rotation = new_rotation * rotation;
-
-                // also, scale horizontally based on launch power
-                let extra_width = power * 0.5;
-                let scale = Vec3::new(
-                    1.0 + extra_width,
-                    1.0 - extra_width * 0.25,
-                    1.0 + extra_width,
-                );
-
-                transform.translation = new_translation;
-                transform.rotation = new_rotation;
-                transform.scale = scale;
-            }
-        }
-        // }
+    for mut transform in q_cannon.iter_mut() {
+        let earth_transform = q_earth.single();
+        let normal = aim.direction();
+        let radius = CANNON_RADIUS;
+        let x = normal.x * radius;
+        let y = normal.y * radius;
+
+        let n = time.delta_seconds() * 16.0;
+
+        let current_translation = transform.translation;
+        let target_translation = Vec3::new(x, y, 0.0) + earth_transform.translation;
+        let current_rotation = transform.rotation;
+        //lookat
+        let target_rotation = Quat::from_rotation_z(-aim.0);
+        let new_translation = current_translation.lerp(target_translation, n);
+        let new_rotation = current_rotation.lerp(target_rotation, n);
+
+        // also, rotate based on launch power
+        let power = launch_power.0.elapsed_secs() * 1.35;
+        let rotation = Quat::from_rotation_y(power * 0.5);
+        let new_rotation = new_rotation * rotation;
+
+        // also, scale horizontally based on launch power
+        let extra_width = power * 0.5;
+        let scale = Vec3::new(
+            1.0 + extra_width,
+            1.0 - extra_width * 0.25,
+            1.0 + extra_width,
+        );
+
+        transform.translation = new_translation;
+        transform.rotation = new_rotation;
+        transform.scale = scale;
     }
 }
 
@@ -1043,9 +765,13 @@ fn update_cannon_transform(
 //     }
 // }
 
-fn rotate_crates(time: Res<Time>, mut q_crate: Query<&mut Transform, With<Crate>>) {
+// idle spin while waiting in the cannon, once launched the simulation turns it
+fn rotate_crates(
+    time: Res<Time>,
+    mut q_crate: Query<&mut Transform, (With<Crate>, Without<Rotation>)>,
+) {
     for mut transform in q_crate.iter_mut() {
-        transform.rotate(Quat::from_rotation_z(time.delta_seconds() * 2.0));
+        transform.rotate(Quat::from_rotation_z(time.delta_seconds() * CRATE_SPIN));
     }
 }
 
@@ -1056,12 +782,6 @@ fn spin_earth(time: Res<Time>, mut q_earth: Query<&mut Transform, With<Earth>>)
     }
 }
 
-fn spin_crates(time: Res<Time>, mut q_crate: Query<&mut Transform, With<Crate>>) {
-    for mut transform in q_crate.iter_mut() {
-        transform.rotate(Quat::from_rotation_z(time.delta_seconds() * 1.0));
-    }
-}
-
 fn spin_debris(
     time: Res<Time>,
     mut q_debris: Query<(Entity, &mut Transform), (With<Debris>, Without<PickedUp>)>,
@@ -1082,77 +802,84 @@ fn spin_debris(
 fn interact_play_button(
     mut q_button: Query<(&Interaction, &mut Style), (Changed<Interaction>, With<PlayButton>)>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut q_instruction_text: Query<
-        (Entity, &mut Style, &mut Text),
-        (With<InstructionText>, Without<PlayButton>),
-    >,
+    mut q_instruction_text: Query<&mut Style, (With<InstructionText>, Without<PlayButton>)>,
 ) {
-    if let Some((interaction, mut style)) = q_button.iter_mut().next() {
-        match interaction {
-            Interaction::Pressed => {
-                style.display = Display::None;
-                next_state.set(GameState::ReadyToLaunch);
-
-                // hide instruction text
-                for (ent, mut style, mut text) in q_instruction_text.iter_mut() {
-                    // set visible
-                    style.display = Display::None;
-                }
-            }
-            _ => {}
-        };
+    if let Some((Interaction::Pressed, mut style)) = q_button.iter_mut().next() {
+        style.display = Display::None;
+        next_state.set(GameState::ReadyToLaunch);
+
+        // hide instruction text
+        for mut style in q_instruction_text.iter_mut() {
+            style.display = Display::None;
+        }
+    }
+}
+
+// the mouse has the play button to click
+fn play_with_keys(input: Res<PlayerInput>, mut next_state: ResMut<NextState<GameState>>) {
+    if input.just_pressed(Action::Confirm) {
+        next_state.set(GameState::ReadyToLaunch);
     }
 }
 
-#[derive(Resource)]
-struct Score(usize);
+fn on_enter_menu(
+    mut commands: Commands,
+    mut q_music: Query<&mut Mixed, With<Music>>,
+    q_instruction_text: Query<Entity, With<InstructionText>>,
+) {
+    // set music volume
+    for mut music in q_music.iter_mut() {
+        music.volume = MENU_MUSIC_VOLUME;
+    }
 
-#[derive(Resource)]
-struct Level(usize);
+    // // despawn instructiontext
+    // for ent in q_instruction_text.iter() {
+    // }
 
-#[derive(Resource)]
-struct LaunchPower(Stopwatch);
+    // show instruction text
+    for ent in q_instruction_text.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
 
-fn on_enter_menu(
+// menu contents depend on the level, which can change without leaving the menu
+fn refresh_menu(
     mut commands: Commands,
-    music_controller: Query<&AudioSink, With<Music>>,
-    mut q_score_text: Query<(Entity, &mut Style, &mut Text), With<ScoreText>>,
-    mut q_instruction_text: Query<
-        (Entity, &mut Style, &mut Text),
-        (With<InstructionText>, Without<ScoreText>),
-    >,
+    mut q_score_text: Query<(&mut Style, &mut Text), With<ScoreText>>,
+    q_menu: Query<Entity, Or<(With<PlayButton>, With<InfoText>)>>,
     level: Res<Level>,
+    levels: Levels,
     mut primary_color_hue: ResMut<PrimaryColorHue>,
+    accessibility: Res<Accessibility>,
+    locale: Res<Locale>,
     score: Res<Score>,
-    // q_instruction_text: Query<Entity, With<InstructionText>>,
 ) {
-    
-
-    // set music volume
-    for sink in music_controller.iter() {
-        sink.set_volume(MENU_MUSIC_VOLUME);
+    for ent in q_menu.iter() {
+        commands.entity(ent).despawn_recursive();
     }
 
     // hehu
     // if reached level 2, replace play button with purple "Endless Mode" button
-    if level.0 > 5 {
-        primary_color_hue.0 = 0.8;
+    if levels.is_endless(&level) {
+        primary_color_hue.0 = accessibility.palette.hue(0.8);
 
         commands
-            .spawn_text_button("Endless Mode", 0.8)
+            .spawn_text_button(&locale.text("menu.endless"), primary_color_hue.0)
             .insert(PlayButton);
     } else {
+        primary_color_hue.0 = accessibility.palette.hue(PRIMARY_COLOR_HUE);
+
         commands
-            .spawn_text_button("Play", PRIMARY_COLOR_HUE)
+            .spawn_text_button(&locale.text("menu.play"), primary_color_hue.0)
             .insert(PlayButton);
     }
 
-    // spawn info text when finishing level 1
-    if level.0 == 6 {
+    // spawn info text when finishing the last campaign level
+    if levels.is_endless(&level) && level.0 == levels.campaign_len() + 1 {
         commands.spawn((
             InfoText,
             TextBundle::from_section(
-                format!("Finished using {} crates!", score.0),
+                locale.count("menu.finished", score.0),
                 TextStyle {
                     font_size: 48.0,
                     color: Color::LIME_GREEN,
@@ -1167,80 +894,49 @@ fn on_enter_menu(
         ));
     }
 
-
     // update level text
-    for (ent, mut style, mut text) in q_score_text.iter_mut() {
+    for (mut style, mut text) in q_score_text.iter_mut() {
         // set visible
         style.display = Display::Flex;
 
         for section in text.sections.iter_mut() {
-            section.value = format!("Level {}", level.0);
+            section.value = locale.format("hud.level", &[("level", &level.0)]);
         }
     }
+}
 
-    // // despawn instructiontext
-    // for ent in q_instruction_text.iter() {
-    // }
-
-    // show instruction text
-    for (ent, mut style, mut text) in q_instruction_text.iter_mut() {
+fn despawn_play_button(mut commands: Commands, q_button: Query<Entity, With<PlayButton>>) {
+    for ent in q_button.iter() {
         commands.entity(ent).despawn_recursive();
-        // set visible
-        // style.display = Display::Flex;
     }
 }
 
-fn on_exit_menu(mut q_score_text: Query<(Entity, &mut Style, &mut Text), With<ScoreText>>) {
-    for (ent, mut style, mut text) in q_score_text.iter_mut() {
+fn on_exit_menu(mut q_score_text: Query<&mut Style, With<ScoreText>>) {
+    for mut style in q_score_text.iter_mut() {
         // set hidden
         style.display = Display::None;
     }
 }
 
-#[derive(Resource)]
-struct GameTime(Stopwatch);
-
 fn on_enter_playing(
     mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut q_score_text: Query<&mut Style, With<ScoreText>>,
-    music_controller: Query<&AudioSink, With<Music>>,
+    mut q_music: Query<&mut Mixed, With<Music>>,
     q_earth: Query<Entity, (With<Earth>, Without<Mercury>)>,
     q_mercury: Query<Entity, (With<Mercury>, Without<Earth>)>,
+    mut q_sun_light: Query<&mut Transform, With<SunLight>>,
     // circle_mesh: Res<AssetHandle<Circle, Mesh>>,
     // circle_mat: Res<AssetHandle<Circle, ColorMaterial>>,
-    debris_scene: Res<AssetHandle<Debris, Scene>>,
-    level: Res<Level>,
-    q_debris: Query<Entity, With<Debris>>,
+    current_level: Res<CurrentLevel>,
     q_info_text: Query<Entity, With<InfoText>>,
-    q_instruction_text: Query<Entity, With<InstructionText>>,
 ) {
     // despawn InfoText
     for ent in q_info_text.iter() {
         commands.entity(ent).despawn_recursive();
     }
 
-    // // if level > 2, hide instruction text
-    // if level.0 > 2 {
-    //     for ent in q_instruction_text.iter() {
-    //         commands.entity(ent).despawn_recursive();
-    //     }
-    // }
-
-    // reset score
-    // score.0 = 0;
-
-    // // hide score text
-    // for mut style in q_score_text.iter_mut() {
-    //     style.display = Display::None;
-    // }
-
-    // start stopwatch
-    commands.insert_resource(GameTime(Stopwatch::new()));
-
     // increase music volume
-    for sink in music_controller.iter() {
-        sink.set_volume(PLAYING_MUSIC_VOLUME);
+    for mut music in q_music.iter_mut() {
+        music.volume = PLAYING_MUSIC_VOLUME;
     }
 
     // spawn one circle
@@ -1267,113 +963,147 @@ fn on_enter_playing(
         commands.entity(mercury_ent).insert(Visibility::Visible);
     }
 
-    // despawn all existing debris
-    for debris_ent in q_debris.iter() {
-        commands.entity(debris_ent).despawn_recursive();
+    // move sun light along with the sun
+    for mut light_transform in q_sun_light.iter_mut() {
+        light_transform.translation.x = current_level.0.sun.x;
+        light_transform.translation.y = current_level.0.sun.y;
     }
+}
 
-    // // spawn debris
-    // for x in -3..=3 {
-    //     for y in -3..=3 {
-    //         commands
-    //             .spawn((
-    //                 Debris,
-    //                 SceneBundle {
-    //                     scene: asset_server.load("debris.glb#Scene0"),
-    //                     transform: Transform::from_xyz(x as f32 * 4.0, y as f32 * 4.0, 0.0)
-    //                         .with_scale(Vec3::splat(1.0))
-    //                         .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0, 0.0, 1.0)),
-    //                     ..default()
-    //                 },
-    //             ));
-    //     }
-    // }
-    // spawn debris in a circle around sun
-    let radius = 22.0 + level.0 as f32 * 1.0;
-    let mut num_debris: i32 = 0 + level.0 as i32 * 1 + (level.0 as i32 - 2).max(0) * 2;
-
-    if level.0 > 4 {
-        num_debris -= 4;
+// closing the tab is how you quit on the web
+fn spawn_quit_button(mut commands: Commands, locale: Res<Locale>) {
+    if cfg!(target_arch = "wasm32") {
+        return;
     }
 
-    // num_debris = 1;
-
-    for i in 0..num_debris {
-        let mut angle = i as f32 / num_debris as f32 * PI * 2.0 + PI - 0.7 + level.0 as f32 + 3.9;
-        angle *= 1.0 + level.0 as f32 * 0.38;
-        let x = angle.sin() * radius + (i as f32 * 12.5 + 1.0).sin() * 5.0;
-        let y = angle.cos() * radius + (i as f32 * 48.3 + 4.0).sin() * 5.0;
-
-        let transform = Transform::from_xyz(x, y + 15.0, 0.0)
-            .with_scale(Vec3::splat(2.0))
-            .with_rotation(Quat::from_euler(EulerRot::XYZ, 1.0 + x, 0.0 + y * 2.0, 0.0));
-
-        commands.spawn((
-            OriginalTransform(transform.clone()),
-            Debris,
-            SceneBundle {
-                scene: debris_scene.handle.clone(),
-                transform: transform,
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
                 ..default()
             },
-            // Velocity(vec2(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5)),
-        ));
+            QuitButton,
+            Localized::new("menu.quit"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("menu.quit"),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_quit_button(mut commands: Commands, q_button: Query<Entity, With<QuitButton>>) {
+    for ent in q_button.iter() {
+        commands.entity(ent).despawn_recursive();
     }
 }
 
-fn on_exit_playing(
-    mut commands: Commands,
-    mut q_score_text: Query<(&mut Style, &mut Text), With<ScoreText>>,
-    score: Res<Score>,
+fn interact_quit_button(
+    q_button: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+    mut exit: EventWriter<AppExit>,
 ) {
-    // display score text
-    for (mut style, mut text) in q_score_text.iter_mut() {
-        style.display = Display::Flex;
-        for section in text.sections.iter_mut() {
-            section.value = format!("Score: {}", score.0);
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed {
+            exit.send(AppExit);
         }
     }
+}
+
+fn duck_music(mut q_music: Query<&mut Mixed, With<Music>>) {
+    for mut music in q_music.iter_mut() {
+        music.volume = PAUSED_MUSIC_VOLUME;
+    }
+}
+
+// the game may be unpausing into the menu, whichever state transition applies first
+fn restore_music(
+    mut q_music: Query<&mut Mixed, With<Music>>,
+    state: Res<State<GameState>>,
+    next_state: Res<NextState<GameState>>,
+) {
+    let to_menu = *state.get() == GameState::Menu || next_state.0 == Some(GameState::Menu);
+    let volume = if to_menu {
+        MENU_MUSIC_VOLUME
+    } else {
+        PLAYING_MUSIC_VOLUME
+    };
 
-    // remove sw
-    commands.remove_resource::<GameTime>();
+    for mut music in q_music.iter_mut() {
+        music.volume = volume;
+    }
 }
 
-fn exit_on_esc(keyboard_input: ResMut<Input<KeyCode>>, mut exit: EventWriter<AppExit>) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
-        exit.send(AppExit);
+// silent until the crate is fired, see update_scream_speed
+fn start_cargo_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    q_crates: Query<(Entity, &CargoSound), Added<CargoSound>>,
+    mixer: Res<Mixer>,
+) {
+    for (crate_ent, sound) in q_crates.iter() {
+        commands.spawn((
+            mixed_audio(
+                asset_server.load(&sound.0),
+                Channel::Ambience,
+                0.0,
+                PlaybackSettings::LOOP,
+                &mixer,
+            ),
+            WhiningSound(crate_ent),
+        ));
     }
 }
 
 fn update_scream_speed(
+    mut commands: Commands,
     // music_controller: Query<&AudioSink, With<Music>>,
-    // sw: Option<Res<GameTime>>,
     time: Res<Time>,
-    q_current_crate: Query<(&Transform, &Velocity, &Crate), With<CurrentCrate>>,
+    q_crates: Query<(&Transform, Option<&Velocity>), With<Crate>>,
     q_sun: Query<&Transform, (With<Sun>, Without<Crate>)>,
-    whining_controller: Query<&AudioSink, With<WhiningSound>>,
+    mut whining_controller: Query<(Entity, &AudioSink, &mut Mixed, &WhiningSound)>,
 ) {
-    if let Ok(whining_sink) = whining_controller.get_single() {
-        let current_volume = whining_sink.volume();
+    for (sound_ent, whining_sink, mut whining, whining_sound) in whining_controller.iter_mut() {
+        let current_volume = whining.volume;
         let current_speed = whining_sink.speed();
 
         let mut target_volume = 0.0;
         let mut target_speed = 0.1;
 
-        if let Ok((crate_transform, crate_velocity, crate_str)) = q_current_crate.get_single() {
-            if let Ok(sun_transform) = q_sun.get_single() {
-                let crate_pos = crate_transform.translation.xy();
-                let sun_pos = sun_transform.translation.xy();
-                let sun_distance = crate_pos.distance(sun_pos); // ranges from 15 to 30
-                let sun_closeness = (1.0 - (sun_distance - 15.0) / 15.0).clamp(0.0, 1.0); // ranges from 0 to 1
+        match q_crates.get(whining_sound.0) {
+            Ok((crate_transform, Some(crate_velocity))) => {
+                if let Ok(sun_transform) = q_sun.get_single() {
+                    let crate_pos = crate_transform.translation.xy();
+                    let sun_pos = sun_transform.translation.xy();
+                    let sun_distance = crate_pos.distance(sun_pos); // ranges from 15 to 30
+                    let sun_closeness = (1.0 - (sun_distance - 15.0) / 15.0).clamp(0.0, 1.0); // ranges from 0 to 1
 
-                let crate_speed = crate_velocity.0.length();
-                let is_poor_fella = crate_str.0 == "Poor fella";
+                    let crate_speed = crate_velocity.0.length();
 
-                if is_poor_fella {
                     target_volume = 0.1 + (crate_speed * 0.1) + sun_closeness * 0.8;
                     target_speed = 0.8 + (crate_speed * 0.1) + sun_closeness * 0.2;
                 }
             }
+            // still in the cannon
+            Ok(_) => {}
+            // gone, let it fade out before stopping it
+            Err(_) => {
+                if current_volume < 0.01 {
+                    commands.entity(sound_ent).despawn_recursive();
+                    continue;
+                }
+            }
         }
 
         let n = time.delta_seconds() * 6.0;
@@ -1382,450 +1112,222 @@ fn update_scream_speed(
         let new_volume = current_volume * (1.0 - n) + target_volume * n;
 
         whining_sink.set_speed(new_speed.clamp(0.1, 20.0));
-        whining_sink.set_volume(new_volume.clamp(0.0, 1.0));
+        // the mixer scales this by the ambience channel
+        whining.volume = new_volume.clamp(0.0, 1.0);
     }
 }
 
-#[derive(Component)]
-struct PickedUp;
-
-fn attach_debris_to_crate_collision(
+// gameplay entities are plain transforms, give them something to look at
+fn add_models(
     mut commands: Commands,
-    mut q_crate: Query<(Entity, &Transform, &mut Mass), With<CurrentCrate>>,
-    mut q_debris: Query<
-        (Entity, &mut Transform),
-        (With<Debris>, Without<PickedUp>, Without<CurrentCrate>),
-    >,
-    slorp_audio_handle: Res<AssetHandle<SlorpSound, AudioSource>>,
-    mut camera_shake: ResMut<CameraShake>,
+    q_sun: Query<Entity, Added<Sun>>,
+    q_earth: Query<Entity, Added<Earth>>,
+    q_level_body: Query<(Entity, Option<&BodyModel>), Added<LevelBody>>,
+    q_cannon: Query<Entity, Added<Cannon>>,
+    q_crate: Query<Entity, Added<Crate>>,
+    q_debris: Query<Entity, Added<Debris>>,
+    sun_mesh: Res<AssetHandle<Sun, Mesh>>,
+    sun_mtl: Res<AssetHandle<Sun, SunMaterial>>,
+    earth_scene: Res<AssetHandle<Earth, Scene>>,
+    mercury_scene: Res<AssetHandle<Mercury, Scene>>,
+    cannon_scene: Res<AssetHandle<Cannon, Scene>>,
+    crate_scene: Res<AssetHandle<Crate, Scene>>,
+    debris_scene: Res<AssetHandle<Debris, Scene>>,
+    asset_server: Res<AssetServer>,
 ) {
-    for (crate_ent, crate_transform, mut crate_mass) in q_crate.iter_mut() {
-        for (debris_ent, mut debris_transform) in q_debris.iter_mut() {
-            let debris_pos = debris_transform.translation.xy();
-            let crate_pos = crate_transform.translation.xy();
-
-            let distance = debris_pos.distance(crate_pos);
-            if distance < 3.7 {
-                // add camera shake
-                camera_shake.0 = 0.1;
-
-                // attach debris to crate
-                commands.entity(crate_ent).add_child(debris_ent);
-                commands.entity(debris_ent).insert(PickedUp);
-
-                let diff = debris_transform.translation - crate_transform.translation;
-
-                // new debris pos = diff transformed by crate rotation
-                let new_debris_pos = crate_transform.rotation.inverse() * diff;
-                debris_transform.translation = new_debris_pos * 0.8;
-
-                // increase crate mass
-                crate_mass.0 += 0.22;
-
-                // play slorp sound
-                commands.spawn((
-                    AudioBundle {
-                        source: slorp_audio_handle.handle.clone().into(),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::Relative(VolumeLevel::new(0.5)),
-                            speed: 2.5,
-                            paused: false,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    SlorpSound,
-                ));
-            }
+    for ent in q_sun.iter() {
+        commands.entity(ent).insert((
+            sun_mesh.handle.clone(),
+            sun_mtl.handle.clone(),
+            VisibilityBundle::default(),
+        ));
+    }
+
+    // mercury, asteroids and anything else the level brings, in mercury's unless it says otherwise
+    for (ent, model) in q_level_body.iter() {
+        let scene = match model {
+            Some(model) => asset_server.load(&model.0),
+            None => mercury_scene.handle.clone(),
+        };
+        commands
+            .entity(ent)
+            .insert((scene, VisibilityBundle::default()));
+    }
+
+    let scenes = [
+        (q_earth.iter().collect::<Vec<_>>(), &earth_scene.handle),
+        (q_cannon.iter().collect(), &cannon_scene.handle),
+        (q_crate.iter().collect(), &crate_scene.handle),
+        (q_debris.iter().collect(), &debris_scene.handle),
+    ];
+    for (entities, scene) in scenes {
+        for ent in entities {
+            commands
+                .entity(ent)
+                .insert((scene.clone(), VisibilityBundle::default()));
         }
     }
 }
 
-fn remove_crate_on_earth_collision(
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut q_crate: Query<(Entity, &Crate, &Transform), With<Crate>>,
-    q_earth: Query<(Entity, &Transform), With<Earth>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    explosion_mesh: Res<AssetHandle<Explosion, Mesh>>,
-    explosion_mtl: Res<AssetHandle<Explosion, StandardMaterial>>,
-    mut camera_shake: ResMut<CameraShake>,
-    mut kill_log: ResMut<KillLog>,
-    mut q_kill_text: Query<(Entity, &mut Style, &mut Text), With<KillLogText>>,
+fn update_kill_log_text(
+    kill_log: Res<KillLog>,
+    locale: Res<Locale>,
+    mut q_kill_text: Query<(&mut Style, &mut Text), With<KillLogText>>,
 ) {
-    for (crate_ent, crate_str, crate_transform) in q_crate.iter_mut() {
-        for (earth_ent, earth_transform) in q_earth.iter() {
-            let earth_pos = earth_transform.translation.xy();
-            let crate_pos = crate_transform.translation.xy();
-            let distance = earth_pos.distance(crate_pos);
-            if distance < 5.0 {
-                // add camera shake
-                camera_shake.0 = 3.0;
-
-                // add crate to kill log
-                kill_log.0.push(crate_str.0.clone());
-                kill_log.0.push("Planet earth".to_string());
-
-                // update kill log text
-                for (ent, mut style, mut text) in q_kill_text.iter_mut() {
-                    // set visible
-                    style.display = Display::Flex;
-
-                    let kill_log_last_five: Vec<String> =
-                        kill_log.0.iter().rev().take(5).rev().cloned().collect();
-
-                    for section in text.sections.iter_mut() {
-                        section.value =
-                            format!("Incineration log:\n{}", kill_log_last_five.join("\n"));
-                    }
-                }
+    if kill_log.0.is_empty() {
+        return;
+    }
 
-                // remove crate
-                commands.entity(crate_ent).despawn_recursive();
-
-                // reset score
-                // score.0 = 0;
-
-                // play earth destroyed sound
-                commands.spawn((
-                    AudioBundle {
-                        source: asset_server.load("earth_destroyed.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::Relative(VolumeLevel::new(0.5)),
-                            paused: false,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    EarthDestroyedSound,
-                ));
-
-                // spawn explosion
-                commands.spawn((
-                    PbrBundle {
-                        mesh: explosion_mesh.handle.clone().into(),
-                        material: explosion_mtl.handle.clone().into(),
-                        transform: Transform::from_xyz(earth_pos.x, earth_pos.y, 1.0)
-                            .with_scale(Vec3::splat(7.0)),
-                        ..default()
-                    },
-                    Explosion,
-                ));
-                for _ in 0..25 {
-                    commands.spawn((
-                        PbrBundle {
-                            mesh: explosion_mesh.handle.clone().into(),
-                            material: explosion_mtl.handle.clone().into(),
-                            transform: Transform::from_xyz(earth_pos.x, earth_pos.y, 1.0)
-                                .with_scale(Vec3::splat(rand::random::<f32>())),
-                            ..default()
-                        },
-                        Explosion,
-                        Velocity(vec2(
-                            rand::random::<f32>() - 0.5,
-                            rand::random::<f32>() - 0.5,
-                        )),
-                    ));
-                }
+    for (mut style, mut text) in q_kill_text.iter_mut() {
+        // set visible
+        style.display = Display::Flex;
 
-                // hide earth
-                commands.entity(earth_ent).insert(Visibility::Hidden);
+        // earth goes down with the crate that hit it
+        let lines = kill_log
+            .0
+            .iter()
+            .flat_map(|entry| match entry.outcome {
+                OnHit::DestroyEarth => {
+                    vec![locale.cargo(&entry.item), locale.text("hud.planet_earth")]
+                }
+                _ => vec![locale.cargo(&entry.item)],
+            })
+            .collect::<Vec<_>>();
+        let kill_log_last_five = &lines[lines.len().saturating_sub(5)..];
 
-                // enter ready to launch state
-                next_state.set(GameState::Menu);
-            }
+        for section in text.sections.iter_mut() {
+            section.value = format!(
+                "{}\n{}",
+                locale.text("hud.kill_log"),
+                kill_log_last_five.join("\n")
+            );
         }
     }
 }
 
-fn remove_crate_on_mercury_collision(
-    asset_server: Res<AssetServer>,
+fn on_debris_picked_up(
     mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut q_crate: Query<(Entity, &Crate, &Transform, &Children), With<Crate>>,
-    q_picked_up_debris: Query<(Entity, &OriginalTransform), (With<Debris>, With<PickedUp>)>,
-    q_mercury: Query<(Entity, &Transform), With<Mercury>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    explosion_mesh: Res<AssetHandle<Explosion, Mesh>>,
-    explosion_mtl: Res<AssetHandle<Explosion, StandardMaterial>>,
+    mut ev_picked_up: EventReader<DebrisCollected>,
+    slorp_audio_handle: Res<AssetHandle<SlorpSound, AudioSource>>,
     mut camera_shake: ResMut<CameraShake>,
-    mut kill_log: ResMut<KillLog>,
-    mut q_kill_text: Query<(Entity, &mut Style, &mut Text), With<KillLogText>>,
-    // q_meshes: Query<(Entity, &Parent), With<World>>,
-    debris_scene: Res<AssetHandle<Debris, Scene>>,
+    mixer: Res<Mixer>,
 ) {
-    for (crate_ent, crate_str, crate_transform, crate_children) in q_crate.iter_mut() {
-        for (mercury_ent, mercury_transform) in q_mercury.iter() {
-            let mercury_pos = mercury_transform.translation.xy();
-            let crate_pos = crate_transform.translation.xy();
-            let distance = mercury_pos.distance(crate_pos);
-            if distance < 3.0 {
-                // add camera shake
-                camera_shake.0 = 3.0;
-
-                // add crate to kill log
-                kill_log.0.push(crate_str.0.clone());
-                // kill_log.0.push("Planet Mercury".to_string());
-
-                // update kill log text
-                for (ent, mut style, mut text) in q_kill_text.iter_mut() {
-                    // set visible
-                    style.display = Display::Flex;
-
-                    let kill_log_last_five: Vec<String> =
-                        kill_log.0.iter().rev().take(5).rev().cloned().collect();
-
-                    for section in text.sections.iter_mut() {
-                        section.value =
-                            format!("Incineration log:\n{}", kill_log_last_five.join("\n"));
-                    }
-                }
-
-                // deparent debris and move to OriginalTransform
-                for (debris_ent, original_transform) in q_picked_up_debris.iter() {
+    for _ in ev_picked_up.iter() {
+        // add camera shake
+        camera_shake.0 = 0.1;
 
-                    // commands.entity(debris_ent).remove::<Parent>();
-                    // commands.entity(debris_ent).remove::<PickedUp>();
-                    // commands.entity(debris_ent).insert(original_transform.0.clone());
-                }
-
-                // respawn pickedup debris
-                for (debris_ent, original_transform) in q_picked_up_debris.iter() {
-                    commands.spawn((
-                        OriginalTransform(original_transform.0.clone()),
-                        Debris,
-                        SceneBundle {
-                            scene: debris_scene.handle.clone(),
-                            transform: original_transform.0,
-                            ..default()
-                        },
-                        // Velocity(vec2(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5)),
-                    ));
-                }
-
-                // // despawn crate
-                // for child in crate_children.iter() {
-                //     q_meshes
-                //         .get(*child)
-                //         .ok()
-                //         .map(|(mesh_ent, _)| commands.entity(mesh_ent).despawn_recursive());
-                // }
-
-                commands.entity(crate_ent).despawn_recursive();
-
-                // reset score
-                // score.0 = 0;
-
-                // play earth destroyed sound
-                commands.spawn((
-                    AudioBundle {
-                        source: asset_server.load("earth_destroyed.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::Relative(VolumeLevel::new(0.5)),
-                            paused: false,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    EarthDestroyedSound,
-                ));
-
-                // spawn explosion
-                commands.spawn((
-                    PbrBundle {
-                        mesh: explosion_mesh.handle.clone().into(),
-                        material: explosion_mtl.handle.clone().into(),
-                        transform: Transform::from_xyz(mercury_pos.x, mercury_pos.y, 1.0)
-                            .with_scale(Vec3::splat(7.0)),
-                        ..default()
-                    },
-                    Explosion,
-                ));
-                for _ in 0..25 {
-                    commands.spawn((
-                        PbrBundle {
-                            mesh: explosion_mesh.handle.clone().into(),
-                            material: explosion_mtl.handle.clone().into(),
-                            transform: Transform::from_xyz(mercury_pos.x, mercury_pos.y, 1.0)
-                                .with_scale(Vec3::splat(rand::random::<f32>())),
-                            ..default()
-                        },
-                        Explosion,
-                        Velocity(vec2(
-                            rand::random::<f32>() - 0.5,
-                            rand::random::<f32>() - 0.5,
-                        )),
-                    ));
-                }
-
-                // // hide mercury
-                // commands.entity(mercury_ent).insert(Visibility::Hidden);
-
-                // enter ready to launch state
-                next_state.set(GameState::ReadyToLaunch);
-            }
-        }
+        // play slorp sound
+        commands.spawn((
+            mixed_audio(
+                slorp_audio_handle.handle.clone(),
+                Channel::Sfx,
+                0.5,
+                PlaybackSettings::DESPAWN.with_speed(2.5),
+                &mixer,
+            ),
+            SlorpSound,
+        ));
     }
 }
 
-fn remove_crate_on_sun_collision(
+fn play_crash_sound(
     asset_server: Res<AssetServer>,
     mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut q_crate: Query<(Entity, &Crate, &GlobalTransform), With<Crate>>,
-    q_sun: Query<&Transform, With<Sun>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    explosion_mesh: Res<AssetHandle<Explosion, Mesh>>,
-    explosion_mtl: Res<AssetHandle<Explosion, StandardMaterial>>,
-    q_floating_debris: Query<Entity, (With<Debris>, Without<PickedUp>)>,
-    mut level: ResMut<Level>,
-    mut kill_log: ResMut<KillLog>,
-    mut q_kill_text: Query<(Entity, &mut Style, &mut Text), With<KillLogText>>,
-    mut camera_shake: ResMut<CameraShake>,
-    success_audio_handle: Res<AssetHandle<SuccessSound, AudioSource>>,
-    mut q_play_button: Query<(Entity), (With<Button>, Without<KillLogText>)>,
+    mut crashes: CrashEvents,
+    mixer: Res<Mixer>,
 ) {
-    for (crate_ent, crate_str, crate_global_transform) in q_crate.iter_mut() {
-        for sun_transform in q_sun.iter() {
-            let sun_pos = sun_transform.translation.xy();
-            let crate_pos = crate_global_transform.translation().xy();
-            let distance = sun_pos.distance(crate_pos);
-            if distance < 13.2 {
-                // add camera shake
-                camera_shake.0 = 2.0;
-
-                // add crate to kill log
-                kill_log.0.push(crate_str.0.clone());
-
-                // update kill log text
-                for (ent, mut style, mut text) in q_kill_text.iter_mut() {
-                    // set visible
-                    style.display = Display::Flex;
-
-                    let kill_log_last_five: Vec<String> =
-                        kill_log.0.iter().rev().take(5).rev().cloned().collect();
-
-                    for section in text.sections.iter_mut() {
-                        section.value =
-                            format!("Incineration log:\n{}", kill_log_last_five.join("\n"));
-                    }
-                }
+    for (on_hit, _) in crashes.iter() {
+        let volume = match on_hit {
+            OnHit::Incinerate => 0.4,
+            OnHit::DestroyEarth | OnHit::SmashCrate => 0.5,
+        };
+
+        // play earth destroyed sound
+        commands.spawn((
+            mixed_audio(
+                asset_server.load("earth_destroyed.ogg"),
+                Channel::Sfx,
+                volume,
+                PlaybackSettings::DESPAWN,
+                &mixer,
+            ),
+            EarthDestroyedSound,
+        ));
+    }
+}
 
-                // remove crate
-                commands.entity(crate_ent).despawn_recursive();
-
-                // increase score
-                // score.0 += 1;
-
-                // play earth destroyed sound
-                commands.spawn((
-                    AudioBundle {
-                        source: asset_server.load("earth_destroyed.ogg"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::Relative(VolumeLevel::new(0.4)),
-                            paused: false,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    EarthDestroyedSound,
-                ));
-
-                // spawn explosion
-                commands.spawn((
-                    PbrBundle {
-                        mesh: explosion_mesh.handle.clone().into(),
-                        material: explosion_mtl.handle.clone().into(),
-                        transform: Transform::from_xyz(crate_pos.x, crate_pos.y, 1.0)
-                            .with_scale(Vec3::splat(2.0)),
-                        ..default()
-                    },
-                    Explosion,
-                ));
-
-                // if 0 debris, increase level
-                let num_debris = q_floating_debris.iter().count();
-                if num_debris == 0 {
-                    level.0 += 1;
-
-                    // play success sound
-                    commands.spawn((
-                        AudioBundle {
-                            source: success_audio_handle.handle.clone().into(),
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Despawn,
-                                volume: Volume::Relative(VolumeLevel::new(0.8)),
-                                speed: 2.5,
-                                paused: false,
-                                ..default()
-                            },
-                            ..default()
-                        },
-                        SuccessSound,
-                    ));
-
-                    // enter menu state for next level
-                    next_state.set(GameState::Menu);
-                } else {
-                    // enter ready to launch state
-                    next_state.set(GameState::ReadyToLaunch);
+fn shake_camera_on_crash(mut crashes: CrashEvents, mut camera_shake: ResMut<CameraShake>) {
+    for (on_hit, _) in crashes.iter() {
+        camera_shake.0 = match on_hit {
+            OnHit::Incinerate => 2.0,
+            OnHit::DestroyEarth | OnHit::SmashCrate => 3.0,
+        };
+    }
+}
+
+fn spawn_crash_explosions(
+    mut crashes: CrashEvents,
+    mut particles: ResMut<ParticlePool>,
+    accessibility: Res<Accessibility>,
+) {
+    for (on_hit, pos) in crashes.iter() {
+        match on_hit {
+            OnHit::Incinerate => particles.burst(&ParticleEmitter::flash(2.0), pos),
+            OnHit::DestroyEarth | OnHit::SmashCrate => {
+                // the big flash fills half the screen
+                if !accessibility.reduced_motion {
+                    particles.burst(&ParticleEmitter::flash(7.0), pos);
                 }
+                particles.burst(&ParticleEmitter::sparks(25), pos);
             }
         }
     }
 }
 
-fn while_playing(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut game_time: ResMut<GameTime>,
-    mut next_state: ResMut<NextState<GameState>>,
+fn apply_reduced_motion(
+    accessibility: Res<Accessibility>,
+    mut sun_materials: ResMut<Assets<SunMaterial>>,
+    mut bg_materials: ResMut<Assets<BackgroundMaterial>>,
 ) {
-    game_time.0.tick(time.delta());
+    for (_, material) in sun_materials.iter_mut() {
+        material.motion = accessibility.motion();
+    }
+    for (_, material) in bg_materials.iter_mut() {
+        material.motion = accessibility.motion();
+    }
 }
 
-fn update_camera_position(
-    mut q_camera: Query<&mut Transform, (With<Camera>, Without<CurrentCrate>)>,
-    q_current_crate: Query<&Transform, With<CurrentCrate>>,
-    time: Res<Time>,
-    shake: Res<CameraShake>,
+fn hide_destroyed_earth(
+    mut commands: Commands,
+    mut ev_earth_destroyed: EventReader<EarthDestroyed>,
+    q_earth: Query<Entity, With<Earth>>,
 ) {
-    let mut target_camera_xy = vec2(0.0, 0.0);
-
-    if let Ok(current_crate_transform) = q_current_crate.get_single() {
-        let crate_xy = current_crate_transform.translation.xy();
-        target_camera_xy = crate_xy;
-    }
-
-    // add shake (based on quantized time)
-    let shake_amount = shake.0;
-    let shake_n = (time.elapsed_seconds() * 100.0).floor();
-    let shake_x = (shake_n * 55.5).sin() * shake_amount;
-    let shake_y = (shake_n * 77.5).cos() * shake_amount;
-    let shake_vec3 = vec3(shake_x, shake_y, 0.0);
-
-    for mut camera_transform in q_camera.iter_mut() {
-        let current_camera_xy = camera_transform.translation.xy();
-        let new_camera_xy = current_camera_xy.lerp(target_camera_xy, time.delta_seconds() * 2.0);
-        let new_camera_translation = vec3(new_camera_xy.x, new_camera_xy.y, 10.0);
-
-        camera_transform.translation = new_camera_translation + shake_vec3;
+    for _ in ev_earth_destroyed.iter() {
+        for earth_ent in q_earth.iter() {
+            commands.entity(earth_ent).insert(Visibility::Hidden);
+        }
     }
 }
 
-fn always(
-    time: Res<Time>,
+fn on_level_cleared(
     mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut camera_shake: ResMut<CameraShake>,
+    mut ev_cleared: EventReader<LevelCleared>,
+    success_audio_handle: Res<AssetHandle<SuccessSound, AudioSource>>,
+    mixer: Res<Mixer>,
 ) {
-    // update camera shake
-    camera_shake.0 *= 1.0 - time.delta_seconds() * 8.0;
+    for _ in ev_cleared.iter() {
+        // play success sound
+        commands.spawn((
+            mixed_audio(
+                success_audio_handle.handle.clone(),
+                Channel::Sfx,
+                0.8,
+                PlaybackSettings::DESPAWN.with_speed(2.5),
+                &mixer,
+            ),
+            SuccessSound,
+        ));
+    }
 }
 
 #[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone)]
@@ -1833,6 +1335,9 @@ fn always(
 pub struct SunMaterial {
     #[uniform(0)]
     color: Color,
+    // 0 holds the animation still
+    #[uniform(0)]
+    motion: f32,
     #[texture(1)]
     #[sampler(2)]
     color_texture: Handle<Image>,
@@ -1852,6 +1357,8 @@ impl Material for SunMaterial {
 pub struct BackgroundMaterial {
     #[uniform(0)]
     color: Color,
+    #[uniform(0)]
+    motion: f32,
     #[texture(1)]
     #[sampler(2)]
     color_texture: Handle<Image>,
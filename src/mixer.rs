@@ -0,0 +1,139 @@
+use bevy::{
+    audio::{Volume, VolumeLevel},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Mixer::default())
+            .add_systems(PostUpdate, apply_mixer);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Music,
+    Sfx,
+    // loops that follow what's going on, like the crate's scream
+    Ambience,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 3] = [Channel::Music, Channel::Sfx, Channel::Ambience];
+
+    // for the string table
+    pub fn key(&self) -> &'static str {
+        match self {
+            Channel::Music => "channel.music",
+            Channel::Sfx => "channel.sfx",
+            Channel::Ambience => "channel.ambience",
+        }
+    }
+}
+
+// volume of each channel, between 0 and 1
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Mixer {
+    pub music: f32,
+    pub sfx: f32,
+    pub ambience: f32,
+    pub muted: bool,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Mixer {
+            music: 1.0,
+            sfx: 1.0,
+            ambience: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl Mixer {
+    pub fn channel(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Music => self.music,
+            Channel::Sfx => self.sfx,
+            Channel::Ambience => self.ambience,
+        }
+    }
+
+    pub fn set_channel(&mut self, channel: Channel, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match channel {
+            Channel::Music => self.music = volume,
+            Channel::Sfx => self.sfx = volume,
+            Channel::Ambience => self.ambience = volume,
+        }
+    }
+
+    // what a sound at full volume on `channel` actually plays at
+    pub fn level(&self, channel: Channel) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.channel(channel)
+        }
+    }
+}
+
+// a sound played through the mixer, `volume` is its own level before the channel's
+#[derive(Component)]
+pub struct Mixed {
+    pub channel: Channel,
+    pub volume: f32,
+}
+
+// every sound is spawned through here, so none of them skip the mixer
+pub fn mixed_audio(
+    source: Handle<AudioSource>,
+    channel: Channel,
+    volume: f32,
+    settings: PlaybackSettings,
+    mixer: &Mixer,
+) -> (AudioBundle, Mixed) {
+    (
+        AudioBundle {
+            source,
+            settings: PlaybackSettings {
+                volume: Volume::Relative(VolumeLevel::new(mixer.level(channel) * volume)),
+                ..settings
+            },
+        },
+        Mixed { channel, volume },
+    )
+}
+
+// sinks only exist once a sound starts, keep them in step with the sliders after that
+fn apply_mixer(mixer: Res<Mixer>, q_sounds: Query<(Ref<Mixed>, Ref<AudioSink>)>) {
+    for (mixed, sink) in q_sounds.iter() {
+        if mixer.is_changed() || mixed.is_changed() || sink.is_added() {
+            sink.set_volume(mixer.level(mixed.channel) * mixed.volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn muting_silences_every_channel() {
+        let mut mixer = Mixer::default();
+        mixer.set_channel(Channel::Music, 0.5);
+        assert_eq!(mixer.level(Channel::Music), 0.5);
+
+        mixer.muted = true;
+        for channel in Channel::ALL {
+            assert_eq!(mixer.level(channel), 0.0);
+        }
+        // the sliders are still where they were when unmuting
+        assert_eq!(mixer.channel(Channel::Music), 0.5);
+    }
+}
@@ -1,11 +1,18 @@
-use bevy::{audio::VolumeLevel, prelude::*};
+use bevy::prelude::*;
+
+use crate::mixer::Mixer;
 
 pub struct MuteButtonPlugin;
 
 impl Plugin for MuteButtonPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (setup_mute_button,))
-            .add_systems(Update, (interact_mute_button,));
+        app.add_systems(Startup, (setup_mute_button,)).add_systems(
+            Update,
+            (
+                interact_mute_button,
+                update_mute_button_image.run_if(resource_changed::<Mixer>()),
+            ),
+        );
     }
 }
 
@@ -15,12 +22,21 @@ struct MuteButton;
 #[derive(Component)]
 struct MuteButtonImage;
 
-fn setup_mute_button(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn mute_button_color(mixer: &Mixer) -> Color {
+    if mixer.muted {
+        Color::WHITE.with_a(0.3)
+    } else {
+        Color::WHITE.with_a(0.6)
+    }
+}
+
+fn setup_mute_button(mut commands: Commands, asset_server: Res<AssetServer>, mixer: Res<Mixer>) {
     commands
         .spawn(ButtonBundle {
             style: Style {
                 right: Val::Px(10.0),
-                top: Val::Px(10.0),
+                // below the quit and pause buttons
+                top: Val::Px(60.0),
                 padding: UiRect::all(Val::Px(5.0)),
                 position_type: PositionType::Absolute,
                 ..default()
@@ -38,34 +54,30 @@ fn setup_mute_button(mut commands: Commands, asset_server: Res<AssetServer>) {
                         ..default()
                     },
                     image: asset_server.load("volume.png").into(),
-                    background_color: Color::WHITE.with_a(0.4).into(),
+                    background_color: mute_button_color(&mixer).into(),
                     ..default()
                 })
                 .insert(MuteButtonImage);
         });
 }
 
+// mutes every channel, the sliders keep their place for when it's turned back on
 fn interact_mute_button(
     q_mute_button: Query<&Interaction, (Changed<Interaction>, With<MuteButton>)>,
-    mut q_mute_button_image: Query<&mut BackgroundColor, With<MuteButtonImage>>,
-    mut global_volume: ResMut<GlobalVolume>,
+    mut mixer: ResMut<Mixer>,
 ) {
-    if let Some(interaction) = q_mute_button.iter().next() {
-        if let Ok(mut background_color) = q_mute_button_image.get_single_mut() {
-            let is_muted = global_volume.volume.get() == 0.0;
-
-            match interaction {
-                Interaction::Pressed => {
-                    if is_muted {
-                        global_volume.volume = VolumeLevel::new(1.0);
-                        background_color.0 = Color::WHITE.with_a(0.6);
-                    } else {
-                        global_volume.volume = VolumeLevel::new(0.0);
-                        background_color.0 = Color::WHITE.with_a(0.3);
-                    }
-                }
-                _ => {}
-            }
+    for interaction in q_mute_button.iter() {
+        if *interaction == Interaction::Pressed {
+            mixer.muted = !mixer.muted;
         }
     }
 }
+
+fn update_mute_button_image(
+    mixer: Res<Mixer>,
+    mut q_mute_button_image: Query<&mut BackgroundColor, With<MuteButtonImage>>,
+) {
+    for mut background_color in q_mute_button_image.iter_mut() {
+        background_color.0 = mute_button_color(&mixer);
+    }
+}
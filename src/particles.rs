@@ -0,0 +1,382 @@
+use std::{f32::consts::PI, sync::Arc};
+
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology, view::NoFrustumCulling},
+};
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticlePool::default())
+            .add_systems(Startup, spawn_particle_mesh)
+            .add_systems(
+                Update,
+                (run_emitters, step_particles, draw_particles).chain(),
+            );
+    }
+}
+
+// every live particle, drawn together as one mesh instead of an entity each
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    particles: Vec<Particle>,
+}
+
+// the whole game happens on one plane, particles are drawn just in front of it
+const PARTICLE_Z: f32 = 1.0;
+
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    // picked once per particle, so they don't all look the same
+    scale: f32,
+    look: Arc<ParticleLook>,
+}
+
+// how particles change over their lifetime, shared by everything one emitter released
+#[derive(Clone)]
+pub struct ParticleLook {
+    pub size: Curve<f32>,
+    // linear rgba, brighter than 1 to bloom
+    pub color: Curve<Vec4>,
+    // fraction of velocity lost per second
+    pub drag: f32,
+}
+
+pub trait Lerp: Copy {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec4 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec4::lerp(self, other, t)
+    }
+}
+
+// values at points between 0 (just released) and 1 (gone), straight lines in between
+#[derive(Clone)]
+pub struct Curve<T>(pub Vec<(f32, T)>);
+
+impl<T: Lerp> Curve<T> {
+    pub fn constant(value: T) -> Self {
+        Curve(vec![(0.0, value)])
+    }
+
+    pub fn sample(&self, t: f32) -> T {
+        let keys = &self.0;
+        let next = keys.iter().position(|(key_t, _)| *key_t > t);
+        match next {
+            None => keys[keys.len() - 1].1,
+            Some(0) => keys[0].1,
+            Some(i) => {
+                let (t0, a) = keys[i - 1];
+                let (t1, b) = keys[i];
+                a.lerp(b, (t - t0) / (t1 - t0))
+            }
+        }
+    }
+}
+
+// releases particles wherever its entity is
+#[derive(Component, Clone)]
+pub struct ParticleEmitter {
+    // particles per second, for as long as the emitter exists
+    pub rate: f32,
+    // released all at once the first time the emitter runs
+    pub burst: usize,
+    pub lifetime: f32,
+    // particles leave within `spread` radians either side of `direction`
+    pub direction: Vec2,
+    pub spread: f32,
+    // units per second, picked between the two
+    pub speed: (f32, f32),
+    // up to this fraction smaller than `look.size` says
+    pub size_jitter: f32,
+    pub look: Arc<ParticleLook>,
+    // particles owed to `rate` that didn't add up to a whole one yet
+    pending: f32,
+    burst_done: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(lifetime: f32, look: ParticleLook) -> Self {
+        ParticleEmitter {
+            rate: 0.0,
+            burst: 0,
+            lifetime,
+            direction: Vec2::Y,
+            spread: PI,
+            speed: (0.0, 0.0),
+            size_jitter: 0.0,
+            look: Arc::new(look),
+            pending: 0.0,
+            burst_done: false,
+        }
+    }
+
+    // a puff every tenth of a second behind a flying crate
+    pub fn crate_trail() -> Self {
+        ParticleEmitter {
+            rate: 10.0,
+            speed: (0.0, 14.0),
+            size_jitter: 1.0,
+            ..ParticleEmitter::new(1.7, fire_look(0.3))
+        }
+    }
+
+    // a single ball of fire that shrinks away, `size` across at first
+    pub fn flash(size: f32) -> Self {
+        ParticleEmitter {
+            burst: 1,
+            ..ParticleEmitter::new(2.5, fire_look(size))
+        }
+    }
+
+    // bits flying off in every direction from something blowing up
+    pub fn sparks(count: usize) -> Self {
+        ParticleEmitter {
+            burst: count,
+            speed: (0.0, 14.0),
+            size_jitter: 1.0,
+            ..ParticleEmitter::new(
+                2.3,
+                ParticleLook {
+                    drag: 0.3,
+                    ..fire_look(1.0)
+                },
+            )
+        }
+    }
+
+    fn release(&self, pool: &mut ParticlePool, position: Vec2, count: usize) {
+        for _ in 0..count {
+            let angle = (rand::random::<f32>() * 2.0 - 1.0) * self.spread;
+            let speed = self.speed.0 + (self.speed.1 - self.speed.0) * rand::random::<f32>();
+            pool.particles.push(Particle {
+                position,
+                velocity: Vec2::from_angle(angle).rotate(self.direction.normalize()) * speed,
+                age: 0.0,
+                lifetime: self.lifetime,
+                scale: 1.0 - self.size_jitter * rand::random::<f32>(),
+                look: self.look.clone(),
+            });
+        }
+    }
+}
+
+// shrinks away like a cooling ember, fast at first
+fn fire_look(size: f32) -> ParticleLook {
+    ParticleLook {
+        size: Curve(vec![
+            (0.0, size),
+            (0.2, size * 0.45),
+            (0.5, size * 0.1),
+            (1.0, 0.0),
+        ]),
+        color: Curve::constant(Vec4::from((Color::ORANGE_RED * 20.0).as_linear_rgba_f32())),
+        drag: 0.0,
+    }
+}
+
+impl ParticlePool {
+    // an emitter's burst at `position`, without keeping the emitter around
+    pub fn burst(&mut self, emitter: &ParticleEmitter, position: Vec2) {
+        emitter.release(self, position, emitter.burst);
+    }
+
+    fn step(&mut self, dt: f32) {
+        for particle in self.particles.iter_mut() {
+            particle.age += dt;
+            particle.velocity *= (1.0 - particle.look.drag).powf(dt);
+            particle.position += particle.velocity * dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+    }
+}
+
+fn run_emitters(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut q_emitters: Query<(&mut ParticleEmitter, &GlobalTransform)>,
+) {
+    for (mut emitter, transform) in q_emitters.iter_mut() {
+        let position = transform.translation().xy();
+
+        if !emitter.burst_done {
+            emitter.burst_done = true;
+            emitter.release(&mut pool, position, emitter.burst);
+        }
+
+        emitter.pending += emitter.rate * time.delta_seconds();
+        let count = emitter.pending.floor();
+        emitter.pending -= count;
+        emitter.release(&mut pool, position, count as usize);
+    }
+}
+
+fn step_particles(time: Res<Time>, mut pool: ResMut<ParticlePool>) {
+    pool.step(time.delta_seconds());
+}
+
+#[derive(Component)]
+struct ParticleMesh;
+
+// corners of the disc every particle is drawn as
+const SEGMENTS: u32 = 16;
+
+fn spawn_particle_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::new(PrimitiveTopology::TriangleList)),
+            // the colour comes from each particle's vertices
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                ..default()
+            }),
+            ..default()
+        },
+        ParticleMesh,
+        // particles go wherever they like, the bounds from spawning would be wrong
+        NoFrustumCulling,
+    ));
+}
+
+// rebuilt from scratch each frame, one disc per particle
+fn draw_particles(
+    pool: Res<ParticlePool>,
+    mut q_mesh: Query<(&Handle<Mesh>, &mut Visibility), With<ParticleMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((handle, mut visibility)) = q_mesh.get_single_mut() else {
+        return;
+    };
+    // nothing to draw, and nothing to upload either
+    if pool.particles.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    let Some(mesh) = meshes.get_mut(handle) else {
+        return;
+    };
+
+    let vertex_count = pool.particles.len() * (SEGMENTS as usize + 1);
+    let mut positions = Vec::with_capacity(vertex_count);
+    let mut colors = Vec::with_capacity(vertex_count);
+    let mut indices = Vec::with_capacity(pool.particles.len() * SEGMENTS as usize * 3);
+
+    for particle in pool.particles.iter() {
+        let t = particle.age / particle.lifetime;
+        let radius = particle.look.size.sample(t) * particle.scale;
+        let color = particle.look.color.sample(t).to_array();
+
+        let center = positions.len() as u32;
+        positions.push(particle.position.extend(PARTICLE_Z).to_array());
+        colors.push(color);
+        for i in 0..SEGMENTS {
+            let offset = Vec2::from_angle(i as f32 / SEGMENTS as f32 * 2.0 * PI) * radius;
+            positions.push((particle.position + offset).extend(PARTICLE_Z).to_array());
+            colors.push(color);
+            indices.extend([center, center + 1 + i, center + 1 + (i + 1) % SEGMENTS]);
+        }
+    }
+
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.set_indices(Some(Indices::U32(indices)));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    #[test]
+    fn curves_blend_between_keys() {
+        let curve = Curve(vec![(0.0, 4.0), (0.5, 2.0), (1.0, 0.0)]);
+        assert_eq!(curve.sample(0.0), 4.0);
+        assert_eq!(curve.sample(0.25), 3.0);
+        assert_eq!(curve.sample(0.75), 1.0);
+        // held past either end
+        assert_eq!(curve.sample(-1.0), 4.0);
+        assert_eq!(curve.sample(2.0), 0.0);
+        assert_eq!(Curve::constant(7.0).sample(0.3), 7.0);
+    }
+
+    #[test]
+    fn particles_slow_down_and_expire() {
+        let mut pool = ParticlePool::default();
+        let emitter = ParticleEmitter {
+            burst: 100,
+            speed: (10.0, 10.0),
+            ..ParticleEmitter::new(
+                1.0,
+                ParticleLook {
+                    drag: 0.5,
+                    ..fire_look(1.0)
+                },
+            )
+        };
+        pool.burst(&emitter, Vec2::ZERO);
+        assert_eq!(pool.particles.len(), 100);
+
+        pool.step(0.5);
+        for particle in pool.particles.iter() {
+            // lost half its speed over a full second, so about 29% over half of one
+            assert!((particle.velocity.length() - 10.0 * 0.5f32.powf(0.5)).abs() < 1e-3);
+        }
+
+        pool.step(0.6);
+        assert_eq!(pool.particles.len(), 0);
+    }
+
+    #[test]
+    fn emitters_release_their_rate_over_time() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                0.1,
+            )))
+            .insert_resource(ParticlePool::default())
+            .add_systems(Update, run_emitters);
+        app.world.spawn((
+            ParticleEmitter {
+                rate: 10.0,
+                burst: 3,
+                ..ParticleEmitter::crate_trail()
+            },
+            GlobalTransform::default(),
+        ));
+
+        // time doesn't move on the first update, so that's just the burst
+        app.update();
+        assert_eq!(app.world.resource::<ParticlePool>().particles.len(), 3);
+
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<ParticlePool>().particles.len(), 3 + 5);
+    }
+}
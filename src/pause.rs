@@ -0,0 +1,384 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    button::{menu_item_style, overlay_panel, ButtonCommands},
+    controls::{Action, PlayerInput},
+    gameplay::{Crate, GameState, RestartLevel},
+    locale::{Locale, Localized},
+    replay::Playback,
+    settings::{OpenSettings, SettingsPanel},
+    PrimaryColorHue,
+};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PauseState>()
+            .add_systems(OnExit(GameState::Menu), spawn_pause_button)
+            .add_systems(OnEnter(GameState::Menu), despawn_pause_button)
+            .add_systems(
+                Update,
+                (
+                    toggle_pause,
+                    restart_and_menu_shortcuts,
+                    interact_pause_button.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(is_playing),
+            )
+            .add_systems(OnEnter(PauseState::Paused), (freeze_time, spawn_pause_menu))
+            .add_systems(
+                OnExit(PauseState::Paused),
+                (unfreeze_time, despawn_pause_menu),
+            )
+            .add_systems(
+                Update,
+                interact_pause_menu.run_if(in_state(PauseState::Paused)),
+            )
+            .add_systems(
+                Update,
+                restart_level.run_if(resource_exists::<RestartRequested>()),
+            );
+    }
+}
+
+// kept apart from GameState, so resuming doesn't run OnEnter(Launched) again
+#[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+#[derive(Component)]
+struct PauseButton;
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Component, Clone, Copy)]
+enum PauseMenuButton {
+    Resume,
+    Restart,
+    Settings,
+    QuitToMenu,
+    QuitGame,
+}
+
+#[derive(Resource)]
+struct RestartRequested;
+
+pub fn is_playing(state: Res<State<GameState>>) -> bool {
+    !matches!(state.get(), GameState::Loading | GameState::Menu)
+}
+
+fn toggle_pause(
+    input: Res<PlayerInput>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if input.just_pressed(Action::Pause) {
+        next_pause_state.set(match pause_state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+// for touch screens, which have no esc
+fn spawn_pause_button(mut commands: Commands) {
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            PauseButton,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "II",
+                TextStyle {
+                    font_size: 32.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_pause_button(mut commands: Commands, q_button: Query<Entity, With<PauseButton>>) {
+    for ent in q_button.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_pause_button(
+    q_button: Query<&Interaction, (Changed<Interaction>, With<PauseButton>)>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed {
+            next_pause_state.set(PauseState::Paused);
+        }
+    }
+}
+
+// FixedUpdate only advances by Time's delta, so this stops the simulation too
+fn freeze_time(mut time: ResMut<Time>) {
+    time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time>) {
+    time.unpause();
+}
+
+fn spawn_pause_menu(
+    mut commands: Commands,
+    primary_color_hue: Res<PrimaryColorHue>,
+    playback: Option<Res<Playback>>,
+    locale: Res<Locale>,
+) {
+    let mut items = vec![("pause.resume", PauseMenuButton::Resume)];
+    // a replay can't start over halfway through
+    if playback.is_none() {
+        items.push(("pause.restart", PauseMenuButton::Restart));
+    }
+    items.push(("settings.title", PauseMenuButton::Settings));
+    items.push(("pause.quit_to_menu", PauseMenuButton::QuitToMenu));
+    // closing the tab is how you quit on the web
+    if cfg!(not(target_arch = "wasm32")) {
+        items.push(("pause.quit_game", PauseMenuButton::QuitGame));
+    }
+
+    let buttons = items
+        .into_iter()
+        .map(|(key, button)| {
+            commands
+                .spawn_text_button(&locale.text(key), primary_color_hue.0)
+                .insert((button, menu_item_style(), Localized::new(key)))
+                .id()
+        })
+        .collect::<Vec<_>>();
+
+    commands
+        .spawn((PauseMenu, overlay_panel(10)))
+        .with_children(|parent| {
+            parent.spawn((
+                Localized::new("pause.title"),
+                TextBundle::from_section(
+                    locale.text("pause.title"),
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            ));
+        })
+        .push_children(&buttons);
+}
+
+fn despawn_pause_menu(
+    mut commands: Commands,
+    q_menu: Query<Entity, Or<(With<PauseMenu>, With<SettingsPanel>)>>,
+) {
+    for ent in q_menu.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_pause_menu(
+    mut commands: Commands,
+    q_button: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    q_crates: Query<Entity, With<Crate>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut ev_open_settings: EventWriter<OpenSettings>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, button) in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            PauseMenuButton::Resume => next_pause_state.set(PauseState::Running),
+            PauseMenuButton::Restart => commands.insert_resource(RestartRequested),
+            PauseMenuButton::Settings => ev_open_settings.send(OpenSettings),
+            PauseMenuButton::QuitToMenu => quit_to_menu(
+                &mut commands,
+                &q_crates,
+                &mut next_state,
+                &mut next_pause_state,
+            ),
+            PauseMenuButton::QuitGame => exit.send(AppExit),
+        }
+    }
+}
+
+// the attempt is abandoned, crate and all
+pub fn quit_to_menu(
+    commands: &mut Commands,
+    q_crates: &Query<Entity, With<Crate>>,
+    next_state: &mut NextState<GameState>,
+    next_pause_state: &mut NextState<PauseState>,
+) {
+    for ent in q_crates.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    next_state.set(GameState::Menu);
+    next_pause_state.set(PauseState::Running);
+}
+
+// the same as picking them from the pause menu, without opening it
+fn restart_and_menu_shortcuts(
+    mut commands: Commands,
+    input: Res<PlayerInput>,
+    q_crates: Query<Entity, With<Crate>>,
+    playback: Option<Res<Playback>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if input.just_pressed(Action::Restart) && playback.is_none() {
+        commands.insert_resource(RestartRequested);
+    } else if input.just_pressed(Action::Menu) {
+        quit_to_menu(
+            &mut commands,
+            &q_crates,
+            &mut next_state,
+            &mut next_pause_state,
+        );
+    }
+}
+
+fn restart_level(world: &mut World) {
+    world.remove_resource::<RestartRequested>();
+    world.run_schedule(RestartLevel);
+
+    // a crate charging or in flight is gone, entering ReadyToLaunch loads the next one
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::ReadyToLaunch);
+    world
+        .resource_mut::<NextState<PauseState>>()
+        .set(PauseState::Running);
+}
+
+// clicks meant for a button shouldn't also fire the cannon
+pub fn pointer_over_ui(q_interaction: Query<&Interaction>) -> bool {
+    q_interaction
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::{
+        gameplay::{self, LaunchPower, SimulationTick, Strokes, Velocity},
+        level::LevelData,
+    };
+
+    fn headless_app() -> App {
+        let mut app = gameplay::headless_app(LevelData {
+            par: 1,
+            sun: vec2(0.0, 15.0),
+            debris: vec![vec2(20.0, 20.0)],
+            ..default()
+        });
+        app.add_state::<PauseState>().add_systems(
+            Update,
+            restart_level.run_if(resource_exists::<RestartRequested>()),
+        );
+        set_state(&mut app, GameState::Menu);
+        set_state(&mut app, GameState::ReadyToLaunch);
+        app
+    }
+
+    fn set_state(app: &mut App, state: GameState) {
+        app.world.resource_mut::<NextState<GameState>>().set(state);
+        app.update();
+    }
+
+    fn restart(app: &mut App) {
+        app.insert_resource(RestartRequested);
+        app.update();
+        app.update();
+    }
+
+    // the state, and how many crates are waiting in the cannon and how many are flying
+    fn crates(app: &mut App) -> (GameState, usize, usize) {
+        let state = app.world.resource::<State<GameState>>().get().clone();
+        let mut q_crates = app.world.query::<(&Crate, Option<&Velocity>)>();
+        let (flying, waiting): (Vec<_>, Vec<_>) = q_crates
+            .iter(&app.world)
+            .partition(|(_, velocity)| velocity.is_some());
+        (state, waiting.len(), flying.len())
+    }
+
+    #[test]
+    fn restart_before_charging_loads_a_fresh_crate() {
+        let mut app = headless_app();
+        assert_eq!(crates(&mut app), (GameState::ReadyToLaunch, 1, 0));
+
+        restart(&mut app);
+        assert_eq!(crates(&mut app), (GameState::ReadyToLaunch, 1, 0));
+        assert!(app.world.resource::<SimulationTick>().0 <= 2);
+    }
+
+    #[test]
+    fn restart_while_charging_loads_a_fresh_crate() {
+        let mut app = headless_app();
+        set_state(&mut app, GameState::ChargingLaunch);
+        app.update();
+        assert!(app.world.resource::<LaunchPower>().0.elapsed_secs() > 0.0);
+
+        restart(&mut app);
+        assert_eq!(crates(&mut app), (GameState::ReadyToLaunch, 1, 0));
+        assert_eq!(app.world.resource::<LaunchPower>().0.elapsed_secs(), 0.0);
+    }
+
+    #[test]
+    fn quitting_while_charging_lets_go_of_the_charge() {
+        let mut app = headless_app();
+        set_state(&mut app, GameState::ChargingLaunch);
+        app.update();
+
+        set_state(&mut app, GameState::Menu);
+        assert_eq!(app.world.resource::<LaunchPower>().0.elapsed_secs(), 0.0);
+    }
+
+    #[test]
+    fn restart_in_flight_starts_the_level_over() {
+        let mut app = headless_app();
+        set_state(&mut app, GameState::ChargingLaunch);
+        app.world
+            .resource_mut::<LaunchPower>()
+            .0
+            .set_elapsed(Duration::from_secs_f32(1.0));
+        set_state(&mut app, GameState::Launched);
+        app.update();
+        assert_eq!(crates(&mut app), (GameState::Launched, 0, 1));
+        assert_eq!(app.world.resource::<Strokes>().0, 1);
+
+        restart(&mut app);
+        assert_eq!(crates(&mut app), (GameState::ReadyToLaunch, 1, 0));
+        assert_eq!(app.world.resource::<Strokes>().0, 0);
+    }
+}
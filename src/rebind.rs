@@ -0,0 +1,448 @@
+use bevy::prelude::*;
+
+use crate::{
+    button::{button_background_color, button_style, overlay_panel, ButtonCommands},
+    controls::{Action, Binding, BindingKind, Bindings},
+    gameplay::GameState,
+    locale::{Locale, Localized},
+    pause::{pointer_over_ui, PauseState},
+    PrimaryColorHue,
+};
+
+pub struct RebindPlugin;
+
+impl Plugin for RebindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OpenControls>()
+            .add_systems(OnExit(GameState::Menu), close_controls)
+            .add_systems(OnExit(PauseState::Paused), close_controls)
+            .add_systems(
+                Update,
+                (
+                    open_controls,
+                    interact_controls_buttons,
+                    capture_binding.run_if(resource_exists::<Listening>()),
+                    update_binding_cells,
+                    update_conflict_text.run_if(
+                        resource_changed::<Bindings>().or_else(resource_changed::<Locale>()),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// show the rebinding screen, on top of settings
+#[derive(Event)]
+pub struct OpenControls;
+
+// waiting for the player to press whatever should trigger `action`
+#[derive(Resource)]
+pub struct Listening {
+    action: Action,
+    kind: BindingKind,
+    // aiming takes two presses, one per direction
+    first: Option<Binding>,
+}
+
+const COLUMNS: [BindingKind; 3] = [
+    BindingKind::Keyboard,
+    BindingKind::Mouse,
+    BindingKind::Gamepad,
+];
+const LABEL_WIDTH: f32 = 160.0;
+const CELL_WIDTH: f32 = 260.0;
+
+#[derive(Component)]
+struct ControlsPanel;
+
+#[derive(Component)]
+struct BindingCell {
+    action: Action,
+    kind: BindingKind,
+}
+
+#[derive(Component)]
+struct ConflictText;
+
+#[derive(Component)]
+enum ControlsButton {
+    ResetDefaults,
+    Back,
+}
+
+fn column_key(kind: BindingKind) -> &'static str {
+    match kind {
+        BindingKind::Keyboard => "controls.keyboard",
+        BindingKind::Mouse => "controls.mouse",
+        BindingKind::Gamepad => "controls.gamepad",
+    }
+}
+
+// the pointer is the only way to aim with a mouse, nothing to pick
+fn is_rebindable(action: Action, kind: BindingKind) -> bool {
+    !(action == Action::Aim && kind == BindingKind::Mouse)
+}
+
+fn conflict_message(bindings: &Bindings, locale: &Locale) -> String {
+    bindings
+        .conflicts()
+        .iter()
+        .map(|(binding, actions)| {
+            let names = actions
+                .iter()
+                .map(|action| action.name(locale))
+                .collect::<Vec<_>>()
+                .join(&locale.text("controls.and"));
+            locale.format(
+                "controls.conflict",
+                &[("binding", &binding.name(locale)), ("actions", &names)],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn text_style(font_size: f32) -> TextStyle {
+    TextStyle {
+        font_size,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+fn open_controls(
+    mut commands: Commands,
+    mut ev_open: EventReader<OpenControls>,
+    q_panel: Query<(), With<ControlsPanel>>,
+    bindings: Res<Bindings>,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    if ev_open.iter().count() == 0 || !q_panel.is_empty() {
+        return;
+    }
+
+    let hue = primary_color_hue.0;
+    let row_style = Style {
+        align_items: AlignItems::Center,
+        margin: UiRect::vertical(Val::Px(2.0)),
+        ..default()
+    };
+    let cell_style = Style {
+        width: Val::Px(CELL_WIDTH),
+        height: Val::Px(32.0),
+        margin: UiRect::horizontal(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        border: UiRect::all(Val::Px(1.0)),
+        ..default()
+    };
+
+    let header = commands
+        .spawn(NodeBundle {
+            style: row_style.clone(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(LABEL_WIDTH),
+                    ..default()
+                },
+                ..default()
+            });
+            for kind in COLUMNS {
+                parent.spawn((
+                    Localized::new(column_key(kind)),
+                    TextBundle::from_section(
+                        locale.text(column_key(kind)),
+                        TextStyle {
+                            color: Color::WHITE.with_a(0.6),
+                            ..text_style(22.0)
+                        },
+                    )
+                    .with_style(Style {
+                        width: Val::Px(CELL_WIDTH),
+                        margin: UiRect::horizontal(Val::Px(4.0)),
+                        ..default()
+                    }),
+                ));
+            }
+        })
+        .id();
+
+    let rows = Action::ALL
+        .into_iter()
+        .map(|action| {
+            let cells = COLUMNS
+                .into_iter()
+                .map(|kind| {
+                    let mut cell = commands.spawn((
+                        ButtonBundle {
+                            style: cell_style.clone(),
+                            border_color: Color::BLACK.with_a(0.5).into(),
+                            background_color: button_background_color(hue).into(),
+                            ..default()
+                        },
+                        BindingCell { action, kind },
+                    ));
+                    cell.with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            bindings.describe(action, kind, &locale),
+                            text_style(20.0),
+                        ));
+                    });
+                    // shown, but there's nothing else it could be
+                    if !is_rebindable(action, kind) {
+                        cell.remove::<Interaction>()
+                            .insert(BackgroundColor(Color::DARK_GRAY));
+                    }
+                    cell.id()
+                })
+                .collect::<Vec<_>>();
+
+            commands
+                .spawn(NodeBundle {
+                    style: row_style.clone(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Localized::new(action.key()),
+                        TextBundle::from_section(action.name(&locale), text_style(24.0))
+                            .with_style(Style {
+                                width: Val::Px(LABEL_WIDTH),
+                                ..default()
+                            }),
+                    ));
+                })
+                .push_children(&cells)
+                .id()
+        })
+        .collect::<Vec<_>>();
+
+    let conflicts = commands
+        .spawn((
+            ConflictText,
+            TextBundle::from_section(
+                conflict_message(&bindings, &locale),
+                TextStyle {
+                    color: Color::ORANGE,
+                    ..text_style(22.0)
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::vertical(Val::Px(10.0)),
+                ..default()
+            }),
+        ))
+        .id();
+
+    let buttons = [
+        ("controls.reset", ControlsButton::ResetDefaults),
+        ("common.back", ControlsButton::Back),
+    ]
+    .into_iter()
+    .map(|(key, button)| {
+        commands
+            .spawn_text_button(&locale.text(key), hue)
+            .insert((
+                button,
+                Localized::new(key),
+                Style {
+                    width: Val::Px(320.0),
+                    height: Val::Px(60.0),
+                    margin: UiRect::all(Val::Px(8.0)),
+                    ..button_style()
+                },
+            ))
+            .id()
+    })
+    .collect::<Vec<_>>();
+    let button_row = commands
+        .spawn(NodeBundle::default())
+        .push_children(&buttons)
+        .id();
+
+    commands
+        .spawn((ControlsPanel, overlay_panel(30)))
+        .with_children(|parent| {
+            parent.spawn((
+                Localized::new("controls.title"),
+                TextBundle::from_section(locale.text("controls.title"), text_style(64.0))
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(20.0)),
+                        ..default()
+                    }),
+            ));
+        })
+        .add_child(header)
+        .push_children(&rows)
+        .add_child(conflicts)
+        .add_child(button_row);
+}
+
+fn close_controls(mut commands: Commands, q_panel: Query<Entity, With<ControlsPanel>>) {
+    for ent in q_panel.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    commands.remove_resource::<Listening>();
+}
+
+fn interact_controls_buttons(
+    mut commands: Commands,
+    q_cells: Query<(&Interaction, &BindingCell), Changed<Interaction>>,
+    q_buttons: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    q_panel: Query<Entity, With<ControlsPanel>>,
+    mut bindings: ResMut<Bindings>,
+) {
+    for (interaction, cell) in q_cells.iter() {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(Listening {
+                action: cell.action,
+                kind: cell.kind,
+                first: None,
+            });
+        }
+    }
+
+    for (interaction, button) in q_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            ControlsButton::ResetDefaults => {
+                *bindings = Bindings::default();
+                commands.remove_resource::<Listening>();
+            }
+            ControlsButton::Back => {
+                for ent in q_panel.iter() {
+                    commands.entity(ent).despawn_recursive();
+                }
+                commands.remove_resource::<Listening>();
+            }
+        }
+    }
+}
+
+// esc, or clicking any other button, gives up
+fn capture_binding(
+    mut commands: Commands,
+    mut listening: ResMut<Listening>,
+    mut bindings: ResMut<Bindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    q_interaction: Query<&Interaction>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<Listening>();
+        return;
+    }
+    let clicked = mouse_button_input.get_just_pressed().next().copied();
+    if clicked.is_some() && pointer_over_ui(q_interaction) {
+        commands.remove_resource::<Listening>();
+        return;
+    }
+
+    let pressed = match listening.kind {
+        BindingKind::Keyboard => keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key)),
+        BindingKind::Mouse => clicked.map(Binding::Mouse),
+        BindingKind::Gamepad => gamepad_input
+            .get_just_pressed()
+            .next()
+            .map(|button| Binding::Gamepad(button.button_type)),
+    };
+    let Some(pressed) = pressed else {
+        return;
+    };
+
+    let binding = match (listening.action, listening.first, pressed) {
+        (Action::Aim, None, _) => {
+            listening.first = Some(pressed);
+            return;
+        }
+        (Action::Aim, Some(Binding::Key(left)), Binding::Key(right)) => Binding::Keys(left, right),
+        (Action::Aim, Some(Binding::Gamepad(left)), Binding::Gamepad(right)) => {
+            Binding::Buttons(left, right)
+        }
+        (_, _, pressed) => pressed,
+    };
+
+    bindings.rebind(listening.action, binding);
+    commands.remove_resource::<Listening>();
+}
+
+fn update_binding_cells(
+    q_cells: Query<(&BindingCell, &Children)>,
+    mut q_text: Query<&mut Text>,
+    bindings: Res<Bindings>,
+    listening: Option<Res<Listening>>,
+    locale: Res<Locale>,
+) {
+    for (cell, children) in q_cells.iter() {
+        let listening = listening
+            .as_ref()
+            .filter(|listening| listening.action == cell.action && listening.kind == cell.kind);
+
+        let (value, color) = match listening {
+            Some(listening) => {
+                let input = locale.text(match cell.kind {
+                    BindingKind::Keyboard => "controls.key",
+                    BindingKind::Mouse => "controls.mouse_button",
+                    BindingKind::Gamepad => "controls.button",
+                });
+                let key = match (cell.action, listening.first) {
+                    (Action::Aim, None) => "controls.press_left",
+                    (Action::Aim, Some(_)) => "controls.press_right",
+                    _ => "controls.press",
+                };
+                let value = locale.format(key, &[("input", &input)]);
+                (value, Color::YELLOW)
+            }
+            None => {
+                let conflicting = bindings
+                    .get(cell.action)
+                    .iter()
+                    .filter(|binding| binding.kind() == cell.kind)
+                    .any(|binding| bindings.is_conflicting(binding));
+                let value = bindings.describe(cell.action, cell.kind, &locale);
+                let value = if value.is_empty() {
+                    "-".to_string()
+                } else {
+                    value
+                };
+                let color = if conflicting {
+                    Color::ORANGE
+                } else {
+                    Color::WHITE
+                };
+                (value, color)
+            }
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(*child) {
+                if text.sections[0].value != value || text.sections[0].style.color != color {
+                    text.sections[0].value = value.clone();
+                    text.sections[0].style.color = color;
+                }
+            }
+        }
+    }
+}
+
+fn update_conflict_text(
+    bindings: Res<Bindings>,
+    locale: Res<Locale>,
+    mut q_text: Query<&mut Text, With<ConflictText>>,
+) {
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = conflict_message(&bindings, &locale);
+    }
+}
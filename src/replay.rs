@@ -0,0 +1,572 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controls::{Action, PlayerInput},
+    endless::EndlessSeed,
+    gameplay::{
+        Aim, CrashEvents, Crate, CrateLaunched, CurrentLevel, DebrisCollected, GameState,
+        LaunchPower, Level, LevelSeed, OnHit, RestartLevel, Score, Simulation, SimulationTick,
+    },
+    kill_log::{Incineration, KillLog},
+    level::Levels,
+    locale::{Locale, Localized},
+    pause::{quit_to_menu, PauseState},
+    storage::{self, StorageDir},
+};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LastReplay(load_replay("replays/last.replay.ron")))
+            .add_systems(
+                OnExit(GameState::Menu),
+                (start_recording.run_if(not(is_replaying)), despawn_replay_ui),
+            )
+            .add_systems(
+                OnEnter(GameState::Menu),
+                (stop_playback, finish_recording, spawn_replay_button).chain(),
+            )
+            // a fresh attempt, the one given up on is kept like it would be going back to the menu
+            .add_systems(
+                RestartLevel,
+                (finish_recording, apply_deferred, start_recording)
+                    .chain()
+                    .run_if(not(is_replaying)),
+            )
+            .add_systems(Update, record_shots.run_if(resource_exists::<Recording>()))
+            .add_systems(Update, skip_playback.run_if(resource_exists::<Playback>()))
+            .add_systems(
+                Update,
+                interact_replay_button.run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(
+                FixedUpdate,
+                drive_playback
+                    .after(Simulation)
+                    .run_if(resource_exists::<Playback>()),
+            );
+    }
+}
+
+// every shot of one attempt at a level, enough to play it back exactly
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    pub level: usize,
+    pub seed: u64,
+    // only matters for endless levels
+    #[serde(default)]
+    pub endless_seed: u64,
+    pub shots: Vec<Shot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Shot {
+    // simulation tick the crate left the cannon
+    pub tick: u32,
+    pub aim: f32,
+    // seconds spent charging, from LaunchPower
+    pub charge: f32,
+    // how the crate's flight ended, checked on playback
+    pub outcome: Option<Outcome>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Outcome {
+    pub cause: OnHit,
+    pub position: Vec2,
+    pub debris: usize,
+}
+
+impl Outcome {
+    // positions get some slack, replays recorded on native may be watched on the web
+    fn matches(&self, other: &Outcome) -> bool {
+        self.cause == other.cause
+            && self.debris == other.debris
+            && self.position.distance(other.position) < 0.01
+    }
+}
+
+#[derive(Resource)]
+struct LastReplay(Option<Replay>);
+
+// the attempt being played right now
+#[derive(Resource)]
+struct Recording {
+    replay: Replay,
+    debris: usize,
+}
+
+// drives GameState from a replay instead of player input
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    // next shot to fire
+    shot: usize,
+    debris: usize,
+    // progress to put back once the replay is over
+    level: usize,
+    score: usize,
+    kill_log: Vec<Incineration>,
+    endless_seed: u64,
+}
+
+#[derive(Component)]
+struct ReplayButton;
+
+#[derive(Component)]
+struct ReplayErrorText;
+
+impl Playback {
+    // put progress back the way it was before watching
+    fn restore(
+        &self,
+        level: &mut Level,
+        score: &mut Score,
+        kill_log: &mut KillLog,
+        endless_seed: &mut EndlessSeed,
+    ) {
+        level.0 = self.level;
+        score.0 = self.score;
+        kill_log.0 = self.kill_log.clone();
+        endless_seed.0 = self.endless_seed;
+    }
+}
+
+pub fn is_replaying(playback: Option<Res<Playback>>) -> bool {
+    playback.is_some()
+}
+
+fn load_replay(key: &str) -> Option<Replay> {
+    let contents = storage::load(StorageDir::Data, key)?;
+    match ron::from_str(&contents) {
+        Ok(replay) => Some(replay),
+        Err(e) => {
+            warn!("ignoring unreadable replay {}: {}", key, e);
+            None
+        }
+    }
+}
+
+fn save_replay(replay: &Replay) {
+    let contents = match ron::ser::to_string_pretty(replay, default()) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("failed to serialize replay: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = storage::save(StorageDir::Data, "replays/last.replay.ron", &contents) {
+        error!("failed to save replay: {}", e);
+    }
+}
+
+fn start_recording(
+    mut commands: Commands,
+    level: Res<Level>,
+    seed: Res<LevelSeed>,
+    endless_seed: Res<EndlessSeed>,
+) {
+    commands.insert_resource(Recording {
+        replay: Replay {
+            level: level.0,
+            seed: seed.0,
+            endless_seed: endless_seed.0,
+            shots: Vec::new(),
+        },
+        debris: 0,
+    });
+}
+
+fn record_shots(
+    mut recording: ResMut<Recording>,
+    mut ev_launched: EventReader<CrateLaunched>,
+    mut ev_picked_up: EventReader<DebrisCollected>,
+    mut crashes: CrashEvents,
+) {
+    for launched in ev_launched.iter() {
+        recording.debris = 0;
+        recording.replay.shots.push(Shot {
+            tick: launched.tick,
+            aim: launched.aim,
+            charge: launched.power,
+            outcome: None,
+        });
+    }
+
+    recording.debris += ev_picked_up.iter().count();
+
+    for (cause, position) in crashes.iter() {
+        let debris = recording.debris;
+        if let Some(shot) = recording.replay.shots.last_mut() {
+            shot.outcome = Some(Outcome {
+                cause,
+                position,
+                debris,
+            });
+        }
+    }
+}
+
+// back in the menu, the attempt is over
+fn finish_recording(
+    mut commands: Commands,
+    recording: Option<Res<Recording>>,
+    mut last_replay: ResMut<LastReplay>,
+) {
+    let Some(recording) = recording else {
+        return;
+    };
+
+    // a crate still in flight when the attempt ended has nothing to check against
+    let mut replay = recording.replay.clone();
+    replay.shots.retain(|shot| shot.outcome.is_some());
+
+    if !replay.shots.is_empty() {
+        save_replay(&replay);
+        last_replay.0 = Some(replay);
+    }
+
+    commands.remove_resource::<Recording>();
+}
+
+// quit halfway through from the pause menu, or skipped
+fn stop_playback(
+    mut commands: Commands,
+    playback: Option<Res<Playback>>,
+    mut level: ResMut<Level>,
+    mut score: ResMut<Score>,
+    mut kill_log: ResMut<KillLog>,
+    mut endless_seed: ResMut<EndlessSeed>,
+) {
+    if let Some(playback) = playback {
+        playback.restore(&mut level, &mut score, &mut kill_log, &mut endless_seed);
+        commands.remove_resource::<Playback>();
+    }
+}
+
+fn skip_playback(
+    mut commands: Commands,
+    input: Res<PlayerInput>,
+    q_crates: Query<Entity, With<Crate>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if input.just_pressed(Action::Skip) {
+        quit_to_menu(
+            &mut commands,
+            &q_crates,
+            &mut next_state,
+            &mut next_pause_state,
+        );
+    }
+}
+
+fn spawn_replay_button(mut commands: Commands, last_replay: Res<LastReplay>, locale: Res<Locale>) {
+    let Some(replay) = &last_replay.0 else {
+        return;
+    };
+    let label = Localized::new("replay.watch").with("level", replay.level);
+    let text = label.text(&locale);
+
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(40.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            ReplayButton,
+            label,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_replay_ui(
+    mut commands: Commands,
+    q_ui: Query<Entity, Or<(With<ReplayButton>, With<ReplayErrorText>)>>,
+) {
+    for ent in q_ui.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_replay_button(
+    mut commands: Commands,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<ReplayButton>)>,
+    last_replay: Res<LastReplay>,
+    mut level: ResMut<Level>,
+    mut seed: ResMut<LevelSeed>,
+    score: Res<Score>,
+    kill_log: Res<KillLog>,
+    endless_seed: Res<EndlessSeed>,
+    levels: Levels,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(replay) = &last_replay.0 else {
+        return;
+    };
+
+    for interaction in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        commands.insert_resource(Playback {
+            replay: replay.clone(),
+            shot: 0,
+            debris: 0,
+            level: level.0,
+            score: score.0,
+            kill_log: kill_log.0.clone(),
+            endless_seed: endless_seed.0,
+        });
+
+        level.0 = replay.level;
+        seed.0 = replay.seed;
+        commands.insert_resource(EndlessSeed(replay.endless_seed));
+        commands.insert_resource(CurrentLevel(
+            levels.level_data_seeded(&level, replay.endless_seed),
+        ));
+
+        next_state.set(GameState::ReadyToLaunch);
+    }
+}
+
+// runs at the end of every fixed step, so transitions land on the same ticks they were recorded on
+fn drive_playback(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    tick: Res<SimulationTick>,
+    fixed_time: Res<FixedTime>,
+    mut aim: ResMut<Aim>,
+    mut launch_power: ResMut<LaunchPower>,
+    mut ev_picked_up: EventReader<DebrisCollected>,
+    mut crashes: CrashEvents,
+    mut level: ResMut<Level>,
+    mut score: ResMut<Score>,
+    mut kill_log: ResMut<KillLog>,
+    mut endless_seed: ResMut<EndlessSeed>,
+    locale: Res<Locale>,
+) {
+    playback.debris += ev_picked_up.iter().count();
+
+    let mut diverged = None;
+    let mut finished = false;
+    for (cause, position) in crashes.iter() {
+        let index = playback.shot.saturating_sub(1);
+        let actual = Outcome {
+            cause,
+            position,
+            debris: playback.debris,
+        };
+        playback.debris = 0;
+
+        match playback
+            .replay
+            .shots
+            .get(index)
+            .and_then(|shot| shot.outcome.as_ref())
+        {
+            Some(expected) if expected.matches(&actual) => {
+                finished = index + 1 == playback.replay.shots.len();
+            }
+            expected => {
+                diverged = Some(format!(
+                    "shot {}: expected {:?}, got {:?}",
+                    index + 1,
+                    expected,
+                    actual
+                ));
+            }
+        }
+    }
+
+    if let Some(message) = &diverged {
+        error!(
+            "replay of level {} diverged, {}",
+            playback.replay.level, message
+        );
+    }
+
+    if diverged.is_some() || finished {
+        playback.restore(&mut level, &mut score, &mut kill_log, &mut endless_seed);
+
+        if diverged.is_some() {
+            commands.spawn((
+                ReplayErrorText,
+                TextBundle::from_section(
+                    locale.text("replay.diverged"),
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::RED,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    margin: UiRect::new(Val::Auto, Val::Auto, Val::Vh(15.0), Val::Auto),
+                    ..default()
+                }),
+            ));
+        }
+
+        // the level may have been cleared, skip straight back to the menu either way
+        commands.remove_resource::<Playback>();
+        next_state.set(GameState::Menu);
+        return;
+    }
+
+    let Some(shot) = playback.replay.shots.get(playback.shot) else {
+        return;
+    };
+    let charge_ticks = (shot.charge / fixed_time.period.as_secs_f32()).round() as u32;
+
+    match state.get() {
+        GameState::ReadyToLaunch if tick.0 + charge_ticks >= shot.tick => {
+            aim.0 = shot.aim;
+            next_state.set(GameState::ChargingLaunch);
+        }
+        GameState::ChargingLaunch if tick.0 >= shot.tick => {
+            aim.0 = shot.aim;
+            launch_power
+                .0
+                .set_elapsed(std::time::Duration::from_secs_f32(shot.charge));
+            next_state.set(GameState::Launched);
+            playback.shot += 1;
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::vec2;
+
+    use super::*;
+    use crate::{gameplay, level::LevelData};
+
+    fn headless_app() -> App {
+        let mut app = gameplay::headless_app(LevelData {
+            sun: vec2(0.0, 15.0),
+            debris: vec![vec2(0.0, -5.0)],
+            ..default()
+        });
+        app.insert_resource(Locale::default())
+            .insert_resource(KillLog::default())
+            .insert_resource(EndlessSeed(0))
+            .add_systems(
+                OnExit(GameState::Menu),
+                start_recording.run_if(not(is_replaying)),
+            )
+            .add_systems(Update, record_shots.run_if(resource_exists::<Recording>()))
+            .add_systems(
+                FixedUpdate,
+                drive_playback
+                    .after(Simulation)
+                    .run_if(resource_exists::<Playback>()),
+            );
+        app
+    }
+
+    fn set_state(app: &mut App, state: GameState) {
+        app.world.resource_mut::<NextState<GameState>>().set(state);
+        app.update();
+    }
+
+    fn state(app: &App) -> GameState {
+        app.world.resource::<State<GameState>>().get().clone()
+    }
+
+    // one shot straight at the sun, through the debris
+    fn record(app: &mut App) -> Replay {
+        set_state(app, GameState::Menu);
+        set_state(app, GameState::ReadyToLaunch);
+        app.insert_resource(Aim::from_direction(Vec2::Y));
+        app.update();
+
+        set_state(app, GameState::ChargingLaunch);
+        for _ in 0..60 {
+            app.update();
+        }
+        set_state(app, GameState::Launched);
+        while state(app) == GameState::Launched {
+            app.update();
+        }
+
+        app.world.resource::<Recording>().replay.clone()
+    }
+
+    // plays the replay through, returning whether it reported a divergence
+    fn play(app: &mut App, replay: Replay) -> bool {
+        app.insert_resource(Playback {
+            replay: replay.clone(),
+            shot: 0,
+            debris: 0,
+            level: 2,
+            score: 1,
+            kill_log: Vec::new(),
+            endless_seed: 0,
+        });
+        app.insert_resource(Level(replay.level));
+        app.insert_resource(LevelSeed(replay.seed));
+        set_state(app, GameState::ReadyToLaunch);
+
+        for _ in 0..60 * 30 {
+            if !app.world.contains_resource::<Playback>() {
+                break;
+            }
+            app.update();
+        }
+        assert!(!app.world.contains_resource::<Playback>());
+        app.update();
+
+        assert_eq!(state(app), GameState::Menu);
+        assert_eq!(app.world.resource::<Level>().0, 2);
+        app.world
+            .query_filtered::<(), With<ReplayErrorText>>()
+            .iter(&app.world)
+            .next()
+            .is_some()
+    }
+
+    #[test]
+    fn recorded_shot_plays_back() {
+        let mut app = headless_app();
+        let replay = record(&mut app);
+
+        assert_eq!(replay.shots.len(), 1);
+        let outcome = replay.shots[0].outcome.as_ref().unwrap();
+        assert_eq!(outcome.cause, OnHit::Incinerate);
+        assert_eq!(outcome.debris, 1);
+        assert!(!play(&mut app, replay));
+    }
+
+    #[test]
+    fn changed_outcome_is_reported_on_playback() {
+        let mut app = headless_app();
+        let mut replay = record(&mut app);
+        replay.shots[0].outcome.as_mut().unwrap().cause = OnHit::SmashCrate;
+
+        assert!(play(&mut app, replay));
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::Accessibility,
+    button::{button_style, set_button_text, ButtonCommands},
+    controls::Bindings,
+    endless::{random_seed, shared_seed, EndlessSeed},
+    gameplay::{CrateIncinerated, GameState, Level, LevelCleared, Score, Simulation},
+    kill_log::KillLog,
+    locale::{system_language, Locale, Localized, LANGUAGES},
+    mixer::{Channel, Mixer},
+    replay::{is_replaying, Playback},
+    storage::{self, StorageDir},
+    trajectory::TrajectoryPreview,
+    PrimaryColorHue,
+};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save())
+            .add_systems(PreStartup, apply_save)
+            .add_systems(OnEnter(GameState::Menu), spawn_reset_button)
+            .add_systems(OnExit(GameState::Menu), despawn_reset_button)
+            .add_systems(
+                Update,
+                interact_reset_button.run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(FixedUpdate, track_progress.after(Simulation))
+            .add_systems(
+                Update,
+                (
+                    sync_mixer.run_if(resource_changed::<Mixer>()),
+                    sync_bindings.run_if(resource_changed::<Bindings>()),
+                    sync_aim_assist.run_if(resource_changed::<TrajectoryPreview>()),
+                    sync_accessibility.run_if(resource_changed::<Accessibility>()),
+                    sync_language.run_if(resource_changed::<Locale>()),
+                    sync_endless_seed
+                        .run_if(resource_changed::<EndlessSeed>().and_then(not(is_replaying))),
+                    write_save.run_if(resource_changed::<SaveData>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+const SAVE_KEY: &str = "save.ron";
+
+// bump when the layout of SaveData changes, and teach `migrate` the old one
+const SAVE_VERSION: u32 = 2;
+
+// everything that should survive a restart
+#[derive(Resource, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    // highest level the player has reached
+    pub unlocked_level: usize,
+    // fewest crates used to clear each level
+    pub best_crates: BTreeMap<usize, usize>,
+    // crates used to clear each level in the current run
+    pub card: BTreeMap<usize, usize>,
+    // items thrown into the sun, ever
+    pub total_incinerated: usize,
+    // crates fired this run, shown when the campaign is finished
+    pub crates_used: usize,
+    // endless levels of this run are generated from it
+    pub endless_seed: Option<u64>,
+    pub mixer: Mixer,
+    pub aim_assist: bool,
+    pub bindings: Bindings,
+    pub accessibility: Accessibility,
+    // picked in settings, None follows the system
+    pub language: Option<String>,
+    // written by a newer version of the game, leave it alone
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        SaveData {
+            version: SAVE_VERSION,
+            unlocked_level: 1,
+            best_crates: BTreeMap::new(),
+            card: BTreeMap::new(),
+            total_incinerated: 0,
+            crates_used: 0,
+            endless_seed: None,
+            mixer: Mixer::default(),
+            aim_assist: false,
+            bindings: Bindings::default(),
+            accessibility: Accessibility::default(),
+            language: None,
+            read_only: false,
+        }
+    }
+}
+
+impl SaveData {
+    // forget progress but keep settings
+    fn reset_progress(&mut self) {
+        *self = SaveData {
+            mixer: self.mixer,
+            aim_assist: self.aim_assist,
+            bindings: self.bindings.clone(),
+            accessibility: self.accessibility,
+            language: self.language.clone(),
+            read_only: self.read_only,
+            ..default()
+        };
+    }
+
+    fn language(&self) -> String {
+        self.language
+            .clone()
+            .or_else(system_language)
+            .unwrap_or_else(|| LANGUAGES[0].to_string())
+    }
+}
+
+// just enough to tell which schema the rest of the file uses
+#[derive(Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+fn load_save() -> SaveData {
+    storage::load(StorageDir::Config, SAVE_KEY)
+        .map_or_else(SaveData::default, |contents| parse_save(&contents))
+}
+
+fn parse_save(contents: &str) -> SaveData {
+    let parsed = ron::from_str::<SaveHeader>(contents)
+        .map_err(|e| e.to_string())
+        .and_then(|header| migrate(header.version, contents));
+
+    match parsed {
+        Ok(save) => save,
+        Err(e) => {
+            // the player's progress is still in there, leave it for a fixed build to read
+            warn!(
+                "ignoring unreadable save, progress will not be saved: {}",
+                e
+            );
+            SaveData {
+                read_only: true,
+                ..default()
+            }
+        }
+    }
+}
+
+// version 1 had one volume for everything, where the mixer is now
+#[derive(Deserialize)]
+struct SaveDataV1 {
+    #[serde(default = "full_volume")]
+    volume: f32,
+}
+
+fn full_volume() -> f32 {
+    1.0
+}
+
+// bring a save written by any earlier version up to SAVE_VERSION
+fn migrate(version: u32, contents: &str) -> Result<SaveData, String> {
+    match version {
+        1 => {
+            // everything else kept its name, so only the volume needs carrying over
+            let old = ron::from_str::<SaveDataV1>(contents).map_err(|e| e.to_string())?;
+            let mut save = ron::from_str::<SaveData>(contents).map_err(|e| e.to_string())?;
+            save.version = SAVE_VERSION;
+            // the old mute button set it to 0, leave the sliders up for unmuting
+            if old.volume <= 0.0 {
+                save.mixer.muted = true;
+            } else {
+                for channel in Channel::ALL {
+                    save.mixer.set_channel(channel, old.volume);
+                }
+            }
+            Ok(save)
+        }
+        SAVE_VERSION => ron::from_str(contents).map_err(|e| e.to_string()),
+        newer if newer > SAVE_VERSION => {
+            warn!(
+                "save is from a newer version ({} > {}), progress will not be saved",
+                newer, SAVE_VERSION
+            );
+            let mut save = ron::from_str::<SaveData>(contents).unwrap_or_default();
+            save.version = newer;
+            save.read_only = true;
+            Ok(save)
+        }
+        older => Err(format!("unknown save version {}", older)),
+    }
+}
+
+fn apply_save(
+    save: Res<SaveData>,
+    mut level: ResMut<Level>,
+    mut score: ResMut<Score>,
+    mut mixer: ResMut<Mixer>,
+    mut preview: ResMut<TrajectoryPreview>,
+    mut endless_seed: ResMut<EndlessSeed>,
+    mut bindings: ResMut<Bindings>,
+    mut accessibility: ResMut<Accessibility>,
+    mut locale: ResMut<Locale>,
+) {
+    level.0 = save.unlocked_level;
+
+    // a seed someone shared beats the one from last time
+    if let (None, Some(seed)) = (shared_seed(), save.endless_seed) {
+        endless_seed.0 = seed;
+    }
+    score.0 = save.crates_used;
+    *mixer = save.mixer;
+    preview.assist = save.aim_assist;
+    *bindings = save.bindings.clone().with_missing_defaults();
+    *accessibility = save.accessibility;
+    locale.language = save.language();
+}
+
+// read in the same fixed step the events are sent, so replays can be told apart
+fn track_progress(
+    mut save: ResMut<SaveData>,
+    mut ev_cleared: EventReader<LevelCleared>,
+    mut ev_incinerated: EventReader<CrateIncinerated>,
+    score: Res<Score>,
+    playback: Option<Res<Playback>>,
+) {
+    // watching a replay doesn't count
+    if playback.is_some() {
+        ev_cleared.clear();
+        ev_incinerated.clear();
+        return;
+    }
+
+    for cleared in ev_cleared.iter() {
+        save.unlocked_level = save.unlocked_level.max(cleared.level + 1);
+        save.card.insert(cleared.level, cleared.strokes);
+
+        let best = save
+            .best_crates
+            .entry(cleared.level)
+            .or_insert(cleared.strokes);
+        *best = (*best).min(cleared.strokes);
+    }
+
+    let incinerated = ev_incinerated.iter().count();
+    if incinerated > 0 {
+        save.total_incinerated += incinerated;
+    }
+
+    if save.crates_used != score.0 {
+        save.crates_used = score.0;
+    }
+}
+
+fn sync_mixer(mixer: Res<Mixer>, mut save: ResMut<SaveData>) {
+    if save.mixer != *mixer {
+        save.mixer = *mixer;
+    }
+}
+
+fn sync_aim_assist(preview: Res<TrajectoryPreview>, mut save: ResMut<SaveData>) {
+    if save.aim_assist != preview.assist {
+        save.aim_assist = preview.assist;
+    }
+}
+
+fn sync_accessibility(accessibility: Res<Accessibility>, mut save: ResMut<SaveData>) {
+    if save.accessibility != *accessibility {
+        save.accessibility = *accessibility;
+    }
+}
+
+// only once something else is picked, until then it keeps following the system
+fn sync_language(locale: Res<Locale>, mut save: ResMut<SaveData>) {
+    if save.language() != locale.language {
+        save.language = Some(locale.language.clone());
+    }
+}
+
+fn sync_bindings(bindings: Res<Bindings>, mut save: ResMut<SaveData>) {
+    if save.bindings != *bindings {
+        save.bindings = bindings.clone();
+    }
+}
+
+fn sync_endless_seed(endless_seed: Res<EndlessSeed>, mut save: ResMut<SaveData>) {
+    if save.endless_seed != Some(endless_seed.0) {
+        save.endless_seed = Some(endless_seed.0);
+    }
+}
+
+fn write_save(save: Res<SaveData>) {
+    if save.read_only {
+        return;
+    }
+
+    let contents = match ron::ser::to_string_pretty(&*save, default()) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("failed to serialize save: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = storage::save(StorageDir::Config, SAVE_KEY, &contents) {
+        error!("failed to write save: {}", e);
+    }
+}
+
+// pressed once to arm, again to actually reset
+#[derive(Component)]
+struct ResetProgressButton {
+    armed: bool,
+}
+
+fn spawn_reset_button(
+    mut commands: Commands,
+    save: Res<SaveData>,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    // nothing to reset yet
+    if save.unlocked_level <= 1 && save.crates_used == 0 {
+        return;
+    }
+
+    commands
+        .spawn_text_button(&locale.text("save.reset"), primary_color_hue.0)
+        .insert((
+            ResetProgressButton { armed: false },
+            Localized::new("save.reset"),
+            Style {
+                width: Val::Px(300.0),
+                height: Val::Px(60.0),
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                margin: UiRect::all(Val::Px(0.0)),
+                ..button_style()
+            },
+        ));
+}
+
+fn despawn_reset_button(
+    mut commands: Commands,
+    q_button: Query<Entity, With<ResetProgressButton>>,
+) {
+    for ent in q_button.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_reset_button(
+    mut commands: Commands,
+    mut q_button: Query<
+        (Entity, &Interaction, &mut ResetProgressButton, &Children),
+        Changed<Interaction>,
+    >,
+    mut q_text: Query<&mut Text>,
+    mut save: ResMut<SaveData>,
+    mut level: ResMut<Level>,
+    mut score: ResMut<Score>,
+    mut kill_log: ResMut<KillLog>,
+    mut endless_seed: ResMut<EndlessSeed>,
+    locale: Res<Locale>,
+) {
+    for (ent, interaction, mut button, children) in q_button.iter_mut() {
+        match interaction {
+            Interaction::Pressed if button.armed => {
+                save.reset_progress();
+                level.0 = 1;
+                score.0 = 0;
+                kill_log.0.clear();
+                endless_seed.0 = random_seed();
+
+                commands.entity(ent).despawn_recursive();
+            }
+            Interaction::Pressed => {
+                button.armed = true;
+
+                set_button_text(children, &mut q_text, &locale.text("save.really_reset"));
+                commands
+                    .entity(ent)
+                    .insert(Localized::new("save.really_reset"));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_save_round_trips() {
+        let mut save = SaveData {
+            unlocked_level: 4,
+            total_incinerated: 12,
+            crates_used: 9,
+            mixer: Mixer {
+                muted: true,
+                ..default()
+            },
+            accessibility: Accessibility {
+                reduced_motion: true,
+                ..default()
+            },
+            language: Some("sv".to_string()),
+            ..default()
+        };
+        save.best_crates.insert(1, 1);
+        save.best_crates.insert(3, 2);
+
+        let contents = ron::to_string(&save).unwrap();
+        assert_eq!(migrate(SAVE_VERSION, &contents).unwrap(), save);
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let save = migrate(SAVE_VERSION, "(version: 2, unlocked_level: 3)").unwrap();
+        assert_eq!(save.unlocked_level, 3);
+        assert_eq!(save.mixer, Mixer::default());
+        assert!(save.best_crates.is_empty());
+    }
+
+    #[test]
+    fn newer_save_is_never_overwritten() {
+        let save = migrate(SAVE_VERSION + 1, "(version: 3, unlocked_level: 7)").unwrap();
+        assert_eq!(save.unlocked_level, 7);
+        assert!(save.read_only);
+    }
+
+    #[test]
+    fn unreadable_save_is_never_overwritten() {
+        let save = parse_save("(version: 2, unlocked_level: \"seven\")");
+        assert_eq!(save.unlocked_level, SaveData::default().unlocked_level);
+        assert!(save.read_only);
+
+        assert!(parse_save("(version: 0, unlocked_level: 7)").read_only);
+        assert!(!parse_save("(version: 2, unlocked_level: 7)").read_only);
+    }
+
+    #[test]
+    fn version_1_volume_moves_to_the_mixer() {
+        let save = migrate(1, "(version: 1, unlocked_level: 5, volume: 0.5)").unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.unlocked_level, 5);
+        assert_eq!(save.mixer.music, 0.5);
+        assert!(!save.mixer.muted);
+
+        let muted = migrate(1, "(version: 1, volume: 0.0)").unwrap();
+        assert!(muted.mixer.muted);
+        assert_eq!(muted.mixer.sfx, 1.0);
+    }
+}
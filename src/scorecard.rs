@@ -0,0 +1,331 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::{
+    button::{button_style, overlay_panel, ButtonCommands},
+    gameplay::{GameState, Level, LevelCleared, Simulation},
+    level::Levels,
+    locale::{Locale, Localized},
+    replay::Playback,
+    save::SaveData,
+    PrimaryColorHue,
+};
+
+pub struct ScorecardPlugin;
+
+impl Plugin for ScorecardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ShowScorecard(false))
+            .add_systems(FixedUpdate, queue_final_scorecard.after(Simulation))
+            .add_systems(
+                OnEnter(GameState::Menu),
+                (spawn_scorecard_button, open_queued_scorecard),
+            )
+            .add_systems(OnExit(GameState::Menu), despawn_scorecard_ui)
+            .add_systems(
+                Update,
+                (interact_scorecard_button, interact_close_button)
+                    .run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+// open the scorecard next time the menu is shown
+#[derive(Resource)]
+struct ShowScorecard(bool);
+
+#[derive(Component)]
+struct ScorecardButton;
+
+#[derive(Component)]
+struct ScorecardPanel;
+
+#[derive(Component)]
+struct CloseScorecardButton;
+
+// golf name for a hole played in `strokes` on a par `par` hole
+pub fn score_name(strokes: usize, par: usize, locale: &Locale) -> String {
+    if strokes == 1 {
+        return locale.text("score.hole_in_one");
+    }
+
+    let key = match strokes as i32 - par as i32 {
+        i32::MIN..=-3 => "score.albatross",
+        -2 => "score.eagle",
+        -1 => "score.birdie",
+        0 => "score.par",
+        1 => "score.bogey",
+        2 => "score.double_bogey",
+        3 => "score.triple_bogey",
+        over => return locale.format("score.over", &[("over", &over)]),
+    };
+    locale.text(key)
+}
+
+// "E" for even, otherwise signed like "+3" or "-1"
+pub fn relative_to_par(strokes: usize, par: usize) -> String {
+    match strokes as i32 - par as i32 {
+        0 => "E".to_string(),
+        over if over > 0 => format!("+{}", over),
+        under => format!("{}", under),
+    }
+}
+
+fn score_color(strokes: usize, par: usize) -> Color {
+    match strokes.cmp(&par) {
+        Ordering::Less => Color::LIME_GREEN,
+        Ordering::Equal => Color::WHITE,
+        Ordering::Greater => Color::ORANGE,
+    }
+}
+
+// the campaign is over, show how it went
+fn queue_final_scorecard(
+    mut ev_cleared: EventReader<LevelCleared>,
+    mut show: ResMut<ShowScorecard>,
+    levels: Levels,
+    playback: Option<Res<Playback>>,
+) {
+    for cleared in ev_cleared.iter() {
+        if playback.is_none() && cleared.level == levels.campaign_len() {
+            show.0 = true;
+        }
+    }
+}
+
+fn open_queued_scorecard(
+    mut commands: Commands,
+    mut show: ResMut<ShowScorecard>,
+    save: Res<SaveData>,
+    levels: Levels,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    if show.0 {
+        show.0 = false;
+        spawn_scorecard(&mut commands, &save, &levels, primary_color_hue.0, &locale);
+    }
+}
+
+fn spawn_scorecard_button(mut commands: Commands, save: Res<SaveData>, locale: Res<Locale>) {
+    if save.card.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(70.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            ScorecardButton,
+            Localized::new("scorecard.title"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("scorecard.title"),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn spawn_scorecard(
+    commands: &mut Commands,
+    save: &SaveData,
+    levels: &Levels,
+    hue: f32,
+    locale: &Locale,
+) {
+    let text_style = TextStyle {
+        font_size: 28.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    // every campaign hole, plus however far into endless the run got
+    let last_hole = save
+        .card
+        .keys()
+        .last()
+        .copied()
+        .unwrap_or(0)
+        .max(levels.campaign_len());
+
+    let mut rows = vec![TextBundle::from_section(
+        format!(
+            "{:<6}{:>5}{:>9}{:>6}",
+            locale.text("scorecard.hole"),
+            locale.text("scorecard.par"),
+            locale.text("scorecard.strokes"),
+            locale.text("scorecard.best")
+        ),
+        TextStyle {
+            color: Color::WHITE.with_a(0.6),
+            ..text_style.clone()
+        },
+    )];
+
+    let mut total_strokes = 0;
+    let mut total_par = 0;
+    for hole in 1..=last_hole {
+        let par = levels.level_data(&Level(hole)).par;
+        let best = save
+            .best_crates
+            .get(&hole)
+            .map(|best| best.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let row = match save.card.get(&hole) {
+            Some(&strokes) => {
+                total_strokes += strokes;
+                total_par += par;
+
+                TextBundle::from_sections([
+                    TextSection::new(
+                        format!("{:<6}{:>5}{:>9}{:>6}  ", hole, par, strokes, best),
+                        text_style.clone(),
+                    ),
+                    TextSection::new(
+                        format!("{:<12}", score_name(strokes, par, locale)),
+                        TextStyle {
+                            color: score_color(strokes, par),
+                            ..text_style.clone()
+                        },
+                    ),
+                ])
+            }
+            None => TextBundle::from_section(
+                format!("{:<6}{:>5}{:>9}{:>6}  {:<12}", hole, par, "-", best, ""),
+                TextStyle {
+                    color: Color::WHITE.with_a(0.4),
+                    ..text_style.clone()
+                },
+            ),
+        };
+        rows.push(row);
+    }
+
+    rows.push(
+        TextBundle::from_section(
+            locale.format(
+                "scorecard.total",
+                &[
+                    ("strokes", &total_strokes),
+                    ("relative", &relative_to_par(total_strokes, total_par)),
+                ],
+            ),
+            TextStyle {
+                font_size: 36.0,
+                color: score_color(total_strokes, total_par),
+                ..text_style.clone()
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::top(Val::Px(20.0)),
+            ..default()
+        }),
+    );
+
+    let close_button = commands
+        .spawn_text_button(&locale.text("common.close"), hue)
+        .insert((
+            CloseScorecardButton,
+            Style {
+                margin: UiRect::top(Val::Px(30.0)),
+                ..button_style()
+            },
+        ))
+        .id();
+
+    commands
+        .spawn((ScorecardPanel, overlay_panel(10)))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("scorecard.title"),
+                TextStyle {
+                    font_size: 64.0,
+                    ..text_style.clone()
+                },
+            ));
+            for row in rows {
+                parent.spawn(row);
+            }
+        })
+        .add_child(close_button);
+}
+
+fn despawn_scorecard_ui(
+    mut commands: Commands,
+    q_ui: Query<Entity, Or<(With<ScorecardButton>, With<ScorecardPanel>)>>,
+) {
+    for ent in q_ui.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_scorecard_button(
+    mut commands: Commands,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<ScorecardButton>)>,
+    q_panel: Query<(), With<ScorecardPanel>>,
+    save: Res<SaveData>,
+    levels: Levels,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed && q_panel.is_empty() {
+            spawn_scorecard(&mut commands, &save, &levels, primary_color_hue.0, &locale);
+        }
+    }
+}
+
+fn interact_close_button(
+    mut commands: Commands,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<CloseScorecardButton>)>,
+    q_panel: Query<Entity, With<ScorecardPanel>>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        for ent in q_panel.iter() {
+            commands.entity(ent).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holes_are_named_relative_to_par() {
+        let locale = Locale::default();
+        assert_eq!(score_name(1, 3, &locale), "Hole in one");
+        assert_eq!(score_name(2, 3, &locale), "Birdie");
+        assert_eq!(score_name(2, 5, &locale), "Albatross");
+        assert_eq!(score_name(4, 4, &locale), "Par");
+        assert_eq!(score_name(5, 4, &locale), "Bogey");
+        assert_eq!(score_name(9, 4, &locale), "5 over");
+    }
+
+    #[test]
+    fn totals_are_relative_to_par() {
+        assert_eq!(relative_to_par(14, 14), "E");
+        assert_eq!(relative_to_par(16, 14), "+2");
+        assert_eq!(relative_to_par(13, 14), "-1");
+    }
+}
@@ -0,0 +1,363 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use crate::{
+    accessibility::OpenAccessibility,
+    button::{button_style, menu_item_style, overlay_panel, set_button_text, ButtonCommands},
+    gameplay::GameState,
+    locale::{Locale, Localized},
+    mixer::{Channel, Mixer},
+    rebind::OpenControls,
+    trajectory::{assist_button_text, TrajectoryPreview},
+    PrimaryColorHue,
+};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OpenSettings>()
+            .add_systems(OnEnter(GameState::Menu), spawn_settings_link)
+            .add_systems(OnExit(GameState::Menu), despawn_settings_link)
+            .add_systems(
+                Update,
+                (
+                    interact_settings_link.run_if(in_state(GameState::Menu)),
+                    open_settings,
+                    interact_settings_buttons,
+                    drag_volume_sliders,
+                    update_volume_sliders.run_if(resource_changed::<Mixer>()),
+                    update_settings_buttons.run_if(
+                        resource_changed::<TrajectoryPreview>()
+                            .or_else(resource_changed::<Locale>()),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// show the settings panel on top of whatever is open
+#[derive(Event)]
+pub struct OpenSettings;
+
+#[derive(Component)]
+pub struct SettingsPanel;
+
+#[derive(Component)]
+enum SettingsButton {
+    AimAssist,
+    // steps through every language that has loaded
+    Language,
+    // nudge a channel up or down, for touch screens and fine tuning
+    Volume(Channel, f32),
+    Controls,
+    Accessibility,
+    Back,
+}
+
+// how far the - and + buttons move a slider
+const VOLUME_STEP: f32 = 0.1;
+const SLIDER_WIDTH: f32 = 200.0;
+
+// click or drag anywhere along it to set the channel's volume
+#[derive(Component)]
+struct VolumeSlider(Channel);
+
+#[derive(Component)]
+struct VolumeSliderFill(Channel);
+
+#[derive(Component)]
+struct SettingsLink;
+
+fn language_button_text(locale: &Locale) -> String {
+    let name = locale.language_name(&locale.language);
+    locale.format("settings.language", &[("language", &name)])
+}
+
+fn spawn_settings_link(mut commands: Commands, locale: Res<Locale>) {
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(130.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            SettingsLink,
+            Localized::new("settings.title"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                locale.text("settings.title"),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_settings_link(
+    mut commands: Commands,
+    q_ui: Query<Entity, Or<(With<SettingsLink>, With<SettingsPanel>)>>,
+) {
+    for ent in q_ui.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_settings_link(
+    q_button: Query<&Interaction, (Changed<Interaction>, With<SettingsLink>)>,
+    mut ev_open: EventWriter<OpenSettings>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed {
+            ev_open.send(OpenSettings);
+        }
+    }
+}
+
+fn open_settings(
+    mut commands: Commands,
+    mut ev_open: EventReader<OpenSettings>,
+    q_panel: Query<(), With<SettingsPanel>>,
+    preview: Res<TrajectoryPreview>,
+    mixer: Res<Mixer>,
+    primary_color_hue: Res<PrimaryColorHue>,
+    locale: Res<Locale>,
+) {
+    if ev_open.iter().count() == 0 || !q_panel.is_empty() {
+        return;
+    }
+
+    let hue = primary_color_hue.0;
+    let sliders = Channel::ALL
+        .into_iter()
+        .map(|channel| spawn_volume_slider(&mut commands, channel, &mixer, hue, &locale))
+        .collect::<Vec<_>>();
+
+    let mut buttons = [
+        (
+            assist_button_text(&preview, &locale),
+            SettingsButton::AimAssist,
+        ),
+        (language_button_text(&locale), SettingsButton::Language),
+    ]
+    .into_iter()
+    .map(|(text, button)| {
+        commands
+            .spawn_text_button(&text, hue)
+            .insert((button, menu_item_style()))
+            .id()
+    })
+    .collect::<Vec<_>>();
+    buttons.extend(
+        [
+            ("settings.controls", SettingsButton::Controls),
+            ("settings.accessibility", SettingsButton::Accessibility),
+            ("common.back", SettingsButton::Back),
+        ]
+        .into_iter()
+        .map(|(key, button)| {
+            commands
+                .spawn_text_button(&locale.text(key), hue)
+                .insert((button, menu_item_style(), Localized::new(key)))
+                .id()
+        }),
+    );
+
+    commands
+        .spawn((SettingsPanel, overlay_panel(20)))
+        .with_children(|parent| {
+            parent.spawn((
+                Localized::new("settings.title"),
+                TextBundle::from_section(
+                    locale.text("settings.title"),
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            ));
+        })
+        .push_children(&sliders)
+        .push_children(&buttons);
+}
+
+// label, -, the slider itself, +
+fn spawn_volume_slider(
+    commands: &mut Commands,
+    channel: Channel,
+    mixer: &Mixer,
+    hue: f32,
+    locale: &Locale,
+) -> Entity {
+    let step_button_style = Style {
+        width: Val::Px(60.0),
+        height: Val::Px(60.0),
+        margin: UiRect::horizontal(Val::Px(10.0)),
+        ..button_style()
+    };
+    let down = commands
+        .spawn_text_button("-", hue)
+        .insert((
+            SettingsButton::Volume(channel, -VOLUME_STEP),
+            step_button_style.clone(),
+        ))
+        .id();
+    let up = commands
+        .spawn_text_button("+", hue)
+        .insert((
+            SettingsButton::Volume(channel, VOLUME_STEP),
+            step_button_style,
+        ))
+        .id();
+
+    let slider = commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(SLIDER_WIDTH),
+                    height: Val::Px(24.0),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                border_color: Color::WHITE.with_a(0.6).into(),
+                background_color: Color::BLACK.with_a(0.5).into(),
+                ..default()
+            },
+            VolumeSlider(channel),
+            RelativeCursorPosition::default(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(mixer.channel(channel) * 100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: Color::WHITE.with_a(0.8).into(),
+                    ..default()
+                },
+                VolumeSliderFill(channel),
+            ));
+        })
+        .id();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                margin: UiRect::vertical(Val::Px(4.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Localized::new(channel.key()),
+                TextBundle::from_section(
+                    locale.text(channel.key()),
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    width: Val::Px(140.0),
+                    ..default()
+                }),
+            ));
+        })
+        .push_children(&[down, slider, up])
+        .id()
+}
+
+fn interact_settings_buttons(
+    mut commands: Commands,
+    q_button: Query<(&Interaction, &SettingsButton), Changed<Interaction>>,
+    q_panel: Query<Entity, With<SettingsPanel>>,
+    mut preview: ResMut<TrajectoryPreview>,
+    mut mixer: ResMut<Mixer>,
+    mut locale: ResMut<Locale>,
+    mut ev_open_controls: EventWriter<OpenControls>,
+    mut ev_open_accessibility: EventWriter<OpenAccessibility>,
+) {
+    for (interaction, button) in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            SettingsButton::AimAssist => preview.assist = !preview.assist,
+            SettingsButton::Language => locale.language = locale.next_language(),
+            SettingsButton::Volume(channel, step) => {
+                let volume = mixer.channel(*channel) + step;
+                // land on whole steps, so ten presses always reach the end
+                mixer.set_channel(*channel, (volume / VOLUME_STEP).round() * VOLUME_STEP);
+            }
+            SettingsButton::Controls => ev_open_controls.send(OpenControls),
+            SettingsButton::Accessibility => ev_open_accessibility.send(OpenAccessibility),
+            SettingsButton::Back => {
+                for ent in q_panel.iter() {
+                    commands.entity(ent).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+// labels that depend on more than the language
+fn update_settings_buttons(
+    q_button: Query<(&SettingsButton, &Children)>,
+    mut q_text: Query<&mut Text>,
+    preview: Res<TrajectoryPreview>,
+    locale: Res<Locale>,
+) {
+    for (button, children) in q_button.iter() {
+        let text = match button {
+            SettingsButton::AimAssist => assist_button_text(&preview, &locale),
+            SettingsButton::Language => language_button_text(&locale),
+            _ => continue,
+        };
+        set_button_text(children, &mut q_text, &text);
+    }
+}
+
+fn drag_volume_sliders(
+    q_slider: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+    mut mixer: ResMut<Mixer>,
+) {
+    for (interaction, cursor, slider) in q_slider.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        // keeps following the cursor past the ends while the button is held
+        if let Some(position) = cursor.normalized {
+            let volume = position.x.clamp(0.0, 1.0);
+            if mixer.channel(slider.0) != volume {
+                mixer.set_channel(slider.0, volume);
+            }
+        }
+    }
+}
+
+fn update_volume_sliders(mixer: Res<Mixer>, mut q_fill: Query<(&VolumeSliderFill, &mut Style)>) {
+    for (fill, mut style) in q_fill.iter_mut() {
+        style.width = Val::Percent(mixer.channel(fill.0) * 100.0);
+    }
+}
@@ -0,0 +1,86 @@
+// small text storage: files under the user's data or config dir natively,
+// window.localStorage on the web. keys look like relative paths, "replays/last.replay.ron"
+
+static APP_DIR: &str = "space-ltd";
+
+#[derive(Clone, Copy, Debug)]
+pub enum StorageDir {
+    // things the game produces, like replays
+    Data,
+    // things the player chooses or earns, like settings and progress
+    Config,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(dir: StorageDir, key: &str) -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library/Application Support"))
+    } else {
+        let (xdg, fallback) = match dir {
+            StorageDir::Data => ("XDG_DATA_HOME", ".local/share"),
+            StorageDir::Config => ("XDG_CONFIG_HOME", ".config"),
+        };
+        env::var_os(xdg)
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(fallback)))
+    };
+
+    Some(base?.join(APP_DIR).join(key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(dir: StorageDir, key: &str) -> Option<String> {
+    std::fs::read_to_string(path(dir, key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(dir: StorageDir, key: &str, contents: &str) -> Result<(), String> {
+    let path = path(dir, key).ok_or("no home directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// where `key` ends up, to show the player
+#[cfg(not(target_arch = "wasm32"))]
+pub fn location(dir: StorageDir, key: &str) -> String {
+    path(dir, key).map_or_else(|| key.to_string(), |path| path.display().to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// both dirs share localStorage, keys are kept apart by prefix
+#[cfg(target_arch = "wasm32")]
+fn storage_key(dir: StorageDir, key: &str) -> String {
+    match dir {
+        StorageDir::Data => format!("{}/data/{}", APP_DIR, key),
+        StorageDir::Config => format!("{}/config/{}", APP_DIR, key),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(dir: StorageDir, key: &str) -> Option<String> {
+    local_storage()?.get_item(&storage_key(dir, key)).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn location(dir: StorageDir, key: &str) -> String {
+    format!("localStorage {}", storage_key(dir, key))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(dir: StorageDir, key: &str, contents: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("localStorage unavailable")?
+        .set_item(&storage_key(dir, key), contents)
+        .map_err(|e| format!("{:?}", e))
+}
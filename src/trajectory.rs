@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::{
+    button::set_button_text,
+    gameplay::{
+        launch_position, launch_velocity, predict_flight, Aim, Attractors, CurrentCrate, Earth,
+        GameState, LaunchPower, Level, Mass, SimulationTick,
+    },
+    locale::Locale,
+};
+
+pub struct TrajectoryPreviewPlugin;
+
+impl Plugin for TrajectoryPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TrajectoryPreview::default())
+            .add_systems(OnEnter(GameState::Menu), spawn_assist_button)
+            .add_systems(OnExit(GameState::Menu), despawn_assist_button)
+            .add_systems(
+                Update,
+                (
+                    interact_assist_button,
+                    // settings has the same toggle
+                    update_assist_button.run_if(
+                        resource_changed::<TrajectoryPreview>()
+                            .or_else(resource_changed::<Locale>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(
+                Update,
+                draw_trajectory_preview
+                    .run_if(in_state(GameState::ChargingLaunch).and_then(preview_enabled)),
+            );
+    }
+}
+
+// dotted line showing where the crate will go if released now
+#[derive(Resource)]
+pub struct TrajectoryPreview {
+    // show on every level
+    pub assist: bool,
+    // levels up to and including this one always show it
+    pub tutorial_levels: usize,
+    // seconds of flight to predict
+    pub length: f32,
+}
+
+impl Default for TrajectoryPreview {
+    fn default() -> Self {
+        TrajectoryPreview {
+            assist: false,
+            tutorial_levels: 2,
+            length: 1.5,
+        }
+    }
+}
+
+// fixed steps between dots
+const DOT_SPACING: usize = 4;
+
+#[derive(Component)]
+struct AssistButton;
+
+fn preview_enabled(preview: Res<TrajectoryPreview>, level: Res<Level>) -> bool {
+    preview.assist || level.0 <= preview.tutorial_levels
+}
+
+pub fn assist_button_text(preview: &TrajectoryPreview, locale: &Locale) -> String {
+    locale.format(
+        "settings.aim_assist",
+        &[("state", &locale.on_off(preview.assist))],
+    )
+}
+
+fn spawn_assist_button(
+    mut commands: Commands,
+    preview: Res<TrajectoryPreview>,
+    locale: Res<Locale>,
+) {
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            AssistButton,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                assist_button_text(&preview, &locale),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE.with_a(0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_assist_button(mut commands: Commands, q_button: Query<Entity, With<AssistButton>>) {
+    for ent in q_button.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn interact_assist_button(
+    q_button: Query<&Interaction, (Changed<Interaction>, With<AssistButton>)>,
+    mut preview: ResMut<TrajectoryPreview>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction == Interaction::Pressed {
+            preview.assist = !preview.assist;
+        }
+    }
+}
+
+fn update_assist_button(
+    q_button: Query<&Children, With<AssistButton>>,
+    mut q_text: Query<&mut Text>,
+    preview: Res<TrajectoryPreview>,
+    locale: Res<Locale>,
+) {
+    for children in q_button.iter() {
+        set_button_text(
+            children,
+            &mut q_text,
+            &assist_button_text(&preview, &locale),
+        );
+    }
+}
+
+fn draw_trajectory_preview(
+    mut gizmos: Gizmos,
+    preview: Res<TrajectoryPreview>,
+    fixed_time: Res<FixedTime>,
+    tick: Res<SimulationTick>,
+    launch_power: Res<LaunchPower>,
+    aim: Res<Aim>,
+    attractors: Attractors,
+    q_crate: Query<&Mass, With<CurrentCrate>>,
+    q_earth: Query<&Transform, With<Earth>>,
+) {
+    let (Ok(mass), Ok(earth)) = (q_crate.get_single(), q_earth.get_single()) else {
+        return;
+    };
+
+    let dt = fixed_time.period.as_secs_f32();
+    let steps = (preview.length / dt).round() as usize;
+    let start = launch_position(earth, &aim);
+    let velocity = launch_velocity(&aim, launch_power.0.elapsed_secs());
+
+    let path = predict_flight(
+        start,
+        velocity,
+        mass.0,
+        &attractors.all(),
+        tick.0,
+        dt,
+        steps,
+    );
+
+    // dots fade out towards the end of the prediction
+    for (i, position) in path
+        .iter()
+        .enumerate()
+        .skip(DOT_SPACING - 1)
+        .step_by(DOT_SPACING)
+    {
+        let fade = 1.0 - i as f32 / steps as f32;
+        gizmos.circle(
+            position.extend(start.z),
+            Vec3::Z,
+            0.25,
+            Color::WHITE.with_a(fade * 0.8),
+        );
+    }
+}
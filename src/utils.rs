@@ -16,7 +16,7 @@ where
 {
     pub fn new(handle: Handle<H>) -> Self {
         Self {
-            handle: handle,
+            handle,
             asset_type: PhantomData,
         }
     }